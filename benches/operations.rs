@@ -0,0 +1,134 @@
+//! Benchmarks for the boolean operations.
+//!
+//! Run with `cargo bench`. To compare with the sweep line status as it was before the treap,
+//! a sorted `Vec` that is spliced at each event, run `cargo bench --features vec-status`.
+//! Results from one machine (median times, 10 samples each):
+//!
+//! ```text
+//! benchmark                 Vec + splice    treap
+//! union_grid/10                 341 µs     590 µs
+//! union_grid/30                 4.69 ms    7.15 ms
+//! union_grid/100                137 ms     169 ms
+//! union_zigzags/10              531 µs     781 µs
+//! union_zigzags/30              4.45 ms    6.23 ms
+//! union_zigzags/100             74.0 ms    79.6 ms
+//! union_zigzags/300             956 ms     1.17 s
+//! union_wide_zigzags/1000       178 ms     125 ms
+//! union_wide_zigzags/3000       1.49 s     350 ms
+//! union_wide_zigzags/10000      14.7 s     1.75 s
+//! ```
+//!
+//! The treap is up to about 1.7 times slower when few edges cross the sweep line at once,
+//! as in the grids and narrow zigzags, since splicing a short `Vec` is a single cheap copy.
+//! But each splice moves every edge after it, so the old sweep was quadratic once many edges cross at once,
+//! as in the wide zigzags, where the treap's lead grows with the input.
+//! We accept the constant factor on small inputs to be rid of the quadratic worst case.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use polygon3::{Line, Polygon};
+use std::convert::TryInto;
+
+fn line(l: [i32; 3]) -> Line {
+    l.try_into().unwrap()
+}
+
+/// The square [x, x + r] × [y, y + r].
+fn square(x: i32, y: i32, r: i32) -> Vec<Line> {
+    vec![
+        line([1, 0, -x]),
+        line([0, 1, -y]),
+        line([-1, 0, x + r]),
+        line([0, -1, y + r]),
+    ]
+}
+
+/// An n × n grid of squares, each overlapping its neighbours.
+fn grid(n: i32) -> Vec<Polygon> {
+    (0..n)
+        .flat_map(|i| (0..n).map(move |j| (i, j)))
+        .map(|(i, j)| Polygon::try_from_edges(vec![square(2 * i, 2 * j, 3)]).unwrap())
+        .collect()
+}
+
+/// The line through two points, with the polygon's interior on its positive side
+/// when the ring runs counterclockwise.
+fn through([px, py]: [i32; 2], [qx, qy]: [i32; 2]) -> Line {
+    line([py - qy, qx - px, px * qy - qx * py])
+}
+
+fn ring(vertices: &[[i32; 2]]) -> Vec<Line> {
+    (0..vertices.len())
+        .map(|i| through(vertices[i], vertices[(i + 1) % vertices.len()]))
+        .collect()
+}
+
+/// `columns` disjoint tall columns side by side, each with zigzag sides of `teeth` teeth.
+/// Every event sees about 2 * `columns` edges crossing the sweep line.
+fn zigzags(columns: i32, teeth: i32) -> Vec<Vec<Line>> {
+    let n = teeth;
+    (0..columns)
+        .map(|i| {
+            let x = 10 * i;
+            let mut vertices = vec![[x, 0]];
+            for j in 0..n {
+                vertices.push([x + 8, 4 * j]);
+                vertices.push([x + 6, 4 * j + 2]);
+            }
+            vertices.push([x + 8, 4 * n]);
+            for j in (0..n).rev() {
+                vertices.push([x, 4 * j + 4]);
+                vertices.push([x + 2, 4 * j + 2]);
+            }
+            ring(&vertices)
+        })
+        .collect()
+}
+
+fn union(c: &mut Criterion) {
+    let mut group = c.benchmark_group("union_grid");
+    group.sample_size(10);
+    for &n in &[10, 30, 100] {
+        let polygons = grid(n);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &polygons, |b, polygons| {
+            b.iter(|| Polygon::union(polygons))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("union_zigzags");
+    group.sample_size(10);
+    for &n in &[10, 30, 100, 300] {
+        let polygons: Vec<Polygon> = zigzags(n, n)
+            .into_iter()
+            .map(|ring| Polygon::try_from_edges(vec![ring]).unwrap())
+            .collect();
+        group.bench_with_input(BenchmarkId::from_parameter(n), &polygons, |b, polygons| {
+            b.iter(|| Polygon::union(polygons))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("union_many_grid");
+    group.sample_size(10);
+    for &n in &[10, 30, 100] {
+        let polygons = grid(n);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &polygons, |b, polygons| {
+            b.iter(|| Polygon::union_many(polygons))
+        });
+    }
+    group.finish();
+
+    // As one polygon, so the cost of tracking many polygons doesn't hide the cost of a wide sweep line.
+    let mut group = c.benchmark_group("union_wide_zigzags");
+    group.sample_size(10);
+    for &n in &[1000, 3000, 10000] {
+        let polygons = vec![Polygon::try_from_edges(zigzags(n, 10)).unwrap()];
+        group.bench_with_input(BenchmarkId::from_parameter(n), &polygons, |b, polygons| {
+            b.iter(|| Polygon::union(polygons))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, union);
+criterion_main!(benches);
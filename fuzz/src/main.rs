@@ -1,9 +1,13 @@
 #[macro_use]
 extern crate afl;
 
-use polygon3::{ConvexPolygon, Line, Point, Polygon};
+use polygon3::{
+    find_intersections, AffineTransform, ConvexPolygon, FillRule, Limits, Line, LineCoord, Point,
+    PolySet, Polygon, ProjectiveTransform, Quantization, Segment,
+};
 use std::cmp::Ordering;
 use std::convert::{TryFrom, TryInto};
+use std::num::NonZeroU32;
 
 fn parse_i32(data: &mut &[u8]) -> Option<i32> {
     if data.len() < 4 {
@@ -54,7 +58,7 @@ fn parse_polygon(data: &mut &[u8]) -> Option<Polygon> {
     for _ in 0..n {
         out.push(parse_edge_vec(data)?);
     }
-    Some(Polygon::try_from_edges(out)?)
+    Polygon::try_from_edges(out).ok()
 }
 
 fn parse_polygons(data: &mut &[u8]) -> Option<Vec<Polygon>> {
@@ -67,6 +71,47 @@ fn parse_polygons(data: &mut &[u8]) -> Option<Vec<Polygon>> {
     Some(out)
 }
 
+fn parse_row(data: &mut &[u8]) -> Option<[i32; 3]> {
+    Some([parse_i32(data)?, parse_i32(data)?, parse_i32(data)?])
+}
+
+fn parse_segments(data: &mut &[u8]) -> Option<Vec<Segment>> {
+    let n = data.get(0)? % 8;
+    *data = &data[1..];
+    let mut out = Vec::new();
+    for _ in 0..n {
+        // The endpoints need not be on the line.
+        out.push(Segment {
+            line: parse_line(data)?,
+            start: parse_point(data)?,
+            end: parse_point(data)?,
+        });
+    }
+    Some(out)
+}
+
+/// Rings of vertices, and the same rings as floating point, which may be infinite or NaN.
+fn parse_vertices(data: &mut &[u8]) -> Option<(Vec<Vec<[LineCoord; 2]>>, Vec<Vec<[f64; 2]>>)> {
+    let n = data.get(0)? % 4;
+    *data = &data[1..];
+    let mut rings = Vec::new();
+    let mut f64_rings = Vec::new();
+    for _ in 0..n {
+        let m = data.get(0)? % 8;
+        *data = &data[1..];
+        let mut ring = Vec::new();
+        let mut f64_ring = Vec::new();
+        for _ in 0..m + 3 {
+            ring.push([parse_i32(data)?.into(), parse_i32(data)?.into()]);
+            let mut coordinate = || Some(f64::from_bits(parse_i64(data)? as u64));
+            f64_ring.push([coordinate()?, coordinate()?]);
+        }
+        rings.push(ring);
+        f64_rings.push(f64_ring);
+    }
+    Some((rings, f64_rings))
+}
+
 fn fuzz_convex_polygon_from_boundaries(mut data: &[u8]) {
     let point;
     if let Some(x) = parse_point(&mut data) {
@@ -155,11 +200,139 @@ fn fuzz_polygon_difference(mut data: &[u8]) {
     }
 }
 
+fn fuzz_make_valid(mut data: &[u8]) {
+    let point = match parse_point(&mut data) {
+        Some(x) if x.sign() == Ordering::Greater => x,
+        Some(x) if x.sign() == Ordering::Less => -x,
+        _ => return,
+    };
+
+    let fill_rule = match data.get(0) {
+        Some(x) if x % 2 == 0 => FillRule::EvenOdd,
+        Some(_) => FillRule::NonZero,
+        None => return,
+    };
+    data = &data[1..];
+
+    if let Some(polygon) = parse_polygon(&mut data) {
+        polygon.test_make_valid(point, fill_rule)
+    }
+}
+
+/// Run every public operation on arbitrary input, and make sure nothing panics.
+fn fuzz_no_panics(mut data: &[u8]) {
+    let point = match parse_point(&mut data) {
+        Some(x) => x,
+        None => return,
+    };
+    let other_point = match parse_point(&mut data) {
+        Some(x) => x,
+        None => return,
+    };
+    let line = match parse_line(&mut data) {
+        Some(x) => x,
+        None => return,
+    };
+    let (affine, projective) = match (
+        parse_row(&mut data),
+        parse_row(&mut data),
+        parse_row(&mut data),
+    ) {
+        (Some(r0), Some(r1), Some(r2)) => (
+            AffineTransform::new([r0, r1]),
+            ProjectiveTransform::new([r0, r1, r2]),
+        ),
+        _ => return,
+    };
+
+    let _ = Line::through(point, other_point);
+    let _ = Line::bisector(point, other_point);
+    if let Some(t) = affine {
+        let _ = t.apply(point);
+        let _ = t.apply(line);
+    }
+    if let Some(t) = projective {
+        let _ = t.apply(point);
+        let _ = t.apply(line);
+    }
+
+    if let Some(segments) = parse_segments(&mut data) {
+        let _ = find_intersections(segments.iter().copied());
+        for &s in &segments {
+            let _ = s.contains(point);
+            for &t in &segments {
+                let _ = s.intersection(t);
+            }
+        }
+    }
+
+    if let Some((rings, f64_rings)) = parse_vertices(&mut data) {
+        if let Ok(polygon) = Polygon::from_vertices(rings) {
+            for spacing in [1, 3, 1 << 20] {
+                let _ = polygon.snap_round(NonZeroU32::new(spacing).unwrap());
+            }
+        }
+        let _ = Polygon::from_f64_vertices(&f64_rings, Quantization::Auto);
+    }
+
+    if let Some(boundaries) = parse_edge_vec(&mut data) {
+        let _ = boundaries.iter().any(|&l| l == line);
+        if let Some(convex_polygon) = ConvexPolygon::from_boundaries(boundaries) {
+            let _ = convex_polygon.contains(point);
+            if let Some(t) = affine {
+                let _ = t.apply(convex_polygon.clone());
+            }
+            if let Some(t) = projective {
+                let _ = t.apply(convex_polygon.clone());
+            }
+            let _ = Polygon::try_from(convex_polygon);
+        }
+    }
+
+    if let Some(polygons) = parse_polygons(&mut data) {
+        for polygon in &polygons {
+            let _ = polygon.contains(point);
+            let _ = polygon.validate();
+            let _ = polygon.make_valid(FillRule::EvenOdd);
+            let _ = polygon.make_valid(FillRule::NonZero);
+            let _ = find_intersections(polygon.rings().flat_map(|ring| ring.edges()));
+            let _ = polygon.difference(&polygons);
+            let _ = polygon.snap_round(NonZeroU32::new(2).unwrap());
+            if let Some(t) = affine {
+                let _ = t.apply(polygon.clone());
+            }
+            if let Some(t) = projective {
+                let _ = t.apply(polygon.clone());
+            }
+        }
+        let _ = Polygon::union(&polygons);
+        let _ = Polygon::intersection(&polygons);
+
+        let limits = Limits {
+            max_events: 5,
+            max_output_edges: 5,
+        };
+        let _ = Polygon::try_operation(&polygons, PolySet::any, limits, || false);
+        // The stream checks its input is sorted, rather than assuming it.
+        let stream = Polygon::operation_streaming(
+            polygons.len(),
+            polygons.iter().cloned().enumerate(),
+            PolySet::any,
+        );
+        let _ = stream.collect::<Vec<_>>();
+        let tagged: Vec<(Polygon, usize)> = polygons.iter().cloned().zip(0..).collect();
+        let _ = Polygon::overlay_with(&tagged, |tags| Some(tags.len()));
+    }
+}
+
 fn main() {
     fuzz!(|data: &[u8]| {
         // fuzz_convex_polygon_from_boundaries(data);
         // fuzz_polygon_containment(data);
         fuzz_polygon_difference(data);
+        // fuzz_make_valid(data);
+        // `fuzz_no_panics` is opt-in, like the others: it checks that every public entry point survives arbitrary input.
+        // fuzz_no_panics(data);
     });
 }
 
@@ -173,6 +173,13 @@ impl ConvexPolygon {
     }
 }
 
+impl ConvexPolygon {
+    /// The caller must ensure the edges are non-redundant, and in clockwise order.
+    pub(crate) fn from_edges_unchecked(edges: Vec<Line>) -> Self {
+        Self(edges)
+    }
+}
+
 impl Into<Vec<Line>> for ConvexPolygon {
     /// Get the edges of a convex polygon, in clockwise order.
     fn into(self) -> Vec<Line> {
@@ -19,8 +19,21 @@
 
 mod convex_polygon;
 mod polygon;
+mod segment;
+mod transform;
 mod utils;
 
 pub use convex_polygon::ConvexPolygon;
-pub use polygon::Polygon;
-pub use utils::{Line, LineMinIntError, Point, PointMinIntError};
+pub use polygon::{
+    find_intersections, EdgeError, EdgeSource, FillRule, GridTransform, Limits, OperationError,
+    OperationStream, PolySet, Polygon, Quantization, QuantizationError, Ring, StreamError,
+    UnionAccumulator, ValidityIssue,
+};
+pub use segment::{Segment, SegmentIntersection};
+pub use transform::{
+    AffineTransform, ProjectiveTransform, ProjectiveTransformable, TransformError, Transformable,
+};
+pub use utils::{
+    Line, LineCoord, LineError, LineMinIntError, OverflowError, Point, PointCoord,
+    PointMinIntError, Rational, Rounding,
+};
@@ -1,9 +1,21 @@
 use crate::convex_polygon::ConvexPolygon;
-use crate::utils::{Line, Point, UnorientedLine};
+use crate::utils::{Line, LineCoord, Point, PointCoord, UnorientedLine};
 use std::cmp::Ordering;
 
 mod contains;
 mod operations;
+mod quantize;
+mod ring;
+mod snap;
+mod validate;
+
+pub use operations::{
+    find_intersections, EdgeSource, FillRule, Limits, OperationError, OperationStream, PolySet,
+    StreamError, UnionAccumulator,
+};
+pub use quantize::{GridTransform, Quantization, QuantizationError};
+pub use ring::Ring;
+pub use validate::ValidityIssue;
 
 /// A bounded region of the plane whose boundary is made of line segments.
 /// May have multiple disconnected components, and may have holes.
@@ -78,27 +90,116 @@ impl Default for Polygon {
     }
 }
 
+/// Why `Polygon::try_from_edges` rejected a ring.
+/// Edges are numbered within their ring, and `edges` holds two consecutive edges,
+/// the second one wrapping around to 0 at the end of the ring.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EdgeError {
+    /// The ring has fewer than 3 edges.
+    TooFewEdges { ring: usize, count: usize },
+    /// Two consecutive edges lie on the same line.
+    IdenticalEdges { ring: usize, edges: [usize; 2] },
+    /// Two consecutive edges are parallel, so they never meet.
+    ParallelEdges { ring: usize, edges: [usize; 2] },
+    /// One of two consecutive edges is the line at infinity, so they meet at infinity.
+    VertexAtInfinity { ring: usize, edges: [usize; 2] },
+}
+
+impl EdgeError {
+    /// The index of the ring that was rejected.
+    pub fn ring(&self) -> usize {
+        match *self {
+            Self::TooFewEdges { ring, .. }
+            | Self::IdenticalEdges { ring, .. }
+            | Self::ParallelEdges { ring, .. }
+            | Self::VertexAtInfinity { ring, .. } => ring,
+        }
+    }
+}
+
+impl std::fmt::Display for EdgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Self::TooFewEdges { ring, count } => {
+                write!(
+                    f,
+                    "Ring {} has {} edges, but needs at least 3.",
+                    ring, count
+                )
+            }
+            Self::IdenticalEdges { ring, edges } => write!(
+                f,
+                "Edges {} and {} of ring {} are the same line.",
+                edges[0], edges[1], ring
+            ),
+            Self::ParallelEdges { ring, edges } => write!(
+                f,
+                "Edges {} and {} of ring {} are parallel.",
+                edges[0], edges[1], ring
+            ),
+            Self::VertexAtInfinity { ring, edges } => write!(
+                f,
+                "Edges {} and {} of ring {} meet at infinity.",
+                edges[0], edges[1], ring
+            ),
+        }
+    }
+}
+impl std::error::Error for EdgeError {}
+
 impl Polygon {
-    pub fn try_from_edges(polys: Vec<Vec<Line>>) -> Option<Self> {
+    /// Make a polygon from rings of edges, where consecutive edges (wrapping around) meet at the ring's vertices.
+    /// Fail on the first ring that has too few edges, or where two consecutive edges don't meet at a finite point.
+    ///
+    /// ```
+    /// # use polygon3::{EdgeError, Polygon};
+    /// # use core::convert::TryInto;
+    /// // Two vertical lines, which never meet.
+    /// let result = Polygon::try_from_edges(vec![vec![
+    ///     [1, 0, 1].try_into().unwrap(),
+    ///     [1, 0, -1].try_into().unwrap(),
+    ///     [0, 1, 1].try_into().unwrap(),
+    /// ]]);
+    /// assert_eq!(
+    ///     result.unwrap_err(),
+    ///     EdgeError::ParallelEdges { ring: 0, edges: [0, 1] }
+    /// );
+    /// ```
+    pub fn try_from_edges(polys: Vec<Vec<Line>>) -> Result<Self, EdgeError> {
         polys
             .into_iter()
-            .map(|edges| {
+            .enumerate()
+            .map(|(ring, edges)| {
                 if edges.len() < 3 {
-                    return None;
+                    return Err(EdgeError::TooFewEdges {
+                        ring,
+                        count: edges.len(),
+                    });
                 }
 
-                let invalid = crate::utils::pairs(&edges).any(|(e1, e2)| {
-                    let [_, _, z]: [i64; 3] = e1.intersect(*e2).into();
-                    z == 0
-                });
+                for (i, (e1, e2)) in crate::utils::pairs(&edges).enumerate() {
+                    let [x, y, z]: [PointCoord; 3] = e1.intersect(*e2).into();
+                    if z != 0 {
+                        continue;
+                    }
 
-                if invalid {
-                    return None;
+                    let edges = [i, (i + 1) % edges.len()];
+                    let at_infinity = |line: &Line| {
+                        let [a, b, _]: [LineCoord; 3] = (*line).into();
+                        a == 0 && b == 0
+                    };
+                    return Err(if x == 0 && y == 0 {
+                        EdgeError::IdenticalEdges { ring, edges }
+                    } else if at_infinity(e1) || at_infinity(e2) {
+                        EdgeError::VertexAtInfinity { ring, edges }
+                    } else {
+                        EdgeError::ParallelEdges { ring, edges }
+                    });
                 }
 
-                Some(edges.into_iter().map(UnorientedLine).collect())
+                Ok(edges.into_iter().map(UnorientedLine).collect())
             })
-            .collect::<Option<_>>()
+            .collect::<Result<_, _>>()
             .map(Self)
     }
 
@@ -112,4 +213,62 @@ impl Polygon {
             })
             .collect()
     }
+
+    /// Replace every edge, keeping the structure of the rings.
+    pub(crate) fn map_edges<E>(
+        self,
+        mut f: impl FnMut(Line) -> Result<Line, E>,
+    ) -> Result<Self, E> {
+        self.0
+            .into_iter()
+            .map(|edges| {
+                edges
+                    .into_iter()
+                    .map(|edge| f(edge.0).map(UnorientedLine))
+                    .collect()
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+#[test]
+fn test_edge_errors() {
+    use std::convert::TryInto;
+
+    let ring = |lines: &[[i32; 3]]| -> Vec<Line> {
+        lines.iter().map(|&l| l.try_into().unwrap()).collect()
+    };
+    let triangle = ring(&[[1, 0, 1], [0, 1, 1], [1, 1, -1]]);
+
+    assert!(Polygon::try_from_edges(vec![triangle.clone()]).is_ok());
+    assert_eq!(
+        Polygon::try_from_edges(vec![triangle.clone(), ring(&[[1, 0, 1], [0, 1, 1]])]).unwrap_err(),
+        EdgeError::TooFewEdges { ring: 1, count: 2 }
+    );
+    assert_eq!(
+        Polygon::try_from_edges(vec![ring(&[[1, 0, 1], [0, 1, 1], [-2, 0, -2]])]).unwrap_err(),
+        EdgeError::IdenticalEdges {
+            ring: 0,
+            edges: [2, 0]
+        }
+    );
+    assert_eq!(
+        Polygon::try_from_edges(vec![triangle, ring(&[[1, 0, 1], [0, 1, 1], [0, 1, 3]])])
+            .unwrap_err(),
+        EdgeError::ParallelEdges {
+            ring: 1,
+            edges: [1, 2]
+        }
+    );
+    let error =
+        Polygon::try_from_edges(vec![ring(&[[1, 0, 1], [0, 0, 1], [1, 1, -1]])]).unwrap_err();
+    assert_eq!(
+        error,
+        EdgeError::VertexAtInfinity {
+            ring: 0,
+            edges: [0, 1]
+        }
+    );
+    assert_eq!(error.ring(), 0);
 }
@@ -1,5 +1,5 @@
 use super::Polygon;
-use crate::utils::{Line, Point};
+use crate::utils::{Line, LineCoord, Point};
 use std::cmp::Ordering;
 
 impl Polygon {
@@ -13,7 +13,21 @@ impl Polygon {
             return Ordering::Less;
         }
 
-        let mut inside = false;
+        match self.winding_number(point) {
+            None => Ordering::Equal,
+            Some(winding) if winding % 2 != 0 => Ordering::Greater,
+            Some(_) => Ordering::Less,
+        }
+    }
+
+    /// How many times do the rings wind counterclockwise around a point?
+    /// Return None if the point is on the boundary, or at infinity.
+    pub(crate) fn winding_number(&self, point: Point) -> Option<i32> {
+        if point.sign() == Ordering::Equal {
+            return None;
+        }
+
+        let mut winding = 0;
 
         for poly in &self.0 {
             let n = poly.len();
@@ -22,7 +36,7 @@ impl Polygon {
                 let k = (i + 2) % n;
 
                 let edge: Line = {
-                    let [_, y, _]: [i32; 3] = poly[j].0.into();
+                    let [_, y, _]: [LineCoord; 3] = poly[j].0.into();
 
                     if y < 0 {
                         -poly[j].0
@@ -36,18 +50,21 @@ impl Polygon {
 
                 match point.cmp_line(edge) {
                     Ordering::Less => {
-                        let x1 = pt_1.x_coord();
-                        let x2 = point.x_coord();
-                        let x3 = pt_2.x_coord();
+                        // Vertices are never at infinity, since `Polygon::try_from_edges` rejects them.
+                        let (x1, x2, x3) = match (pt_1.x_coord(), point.x_coord(), pt_2.x_coord()) {
+                            (Some(x1), Some(x2), Some(x3)) => (x1, x2, x3),
+                            _ => return None,
+                        };
 
                         if (x1 < x2) ^ (x3 < x2) {
-                            inside ^= true;
+                            // The edge passes above the point, going right (clockwise) or left (counterclockwise).
+                            winding += if x1 < x3 { -1 } else { 1 };
                         }
                     }
                     Ordering::Equal => {
                         match point.cmp_line(poly[i].0) {
                             Ordering::Equal => {
-                                return Ordering::Equal;
+                                return None;
                             }
                             o if o != pt_2.cmp_line(poly[i].0) => {
                                 continue;
@@ -56,25 +73,21 @@ impl Polygon {
                         }
                         match point.cmp_line(poly[k].0) {
                             Ordering::Equal => {
-                                return Ordering::Equal;
+                                return None;
                             }
                             o if o != pt_1.cmp_line(poly[k].0) => {
                                 continue;
                             }
                             _ => {}
                         }
-                        return Ordering::Equal;
+                        return None;
                     }
                     Ordering::Greater => {}
                 }
             }
         }
 
-        if inside {
-            Ordering::Greater
-        } else {
-            Ordering::Less
-        }
+        Some(winding)
     }
 }
 
@@ -94,7 +107,7 @@ fn test_square() {
         .try_into()
         .unwrap();
 
-    for x in -2..=2 {
+    for x in -2_i64..=2 {
         for y in -2..=2 {
             let point: Point = [x, y, 1].try_into().unwrap();
 
@@ -120,7 +133,7 @@ fn test_diamond() {
         .try_into()
         .unwrap();
 
-    for x in -2..=2 {
+    for x in -2_i64..=2 {
         for y in -2..=2 {
             let point: Point = [x, y, 1].try_into().unwrap();
 
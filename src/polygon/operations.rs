@@ -1,12 +1,25 @@
+mod accumulator;
+mod intersections;
+mod overlay;
+mod poly_set;
+mod provenance;
 mod queue;
+mod stream;
 mod sweep_line;
 
 #[cfg(test)]
 mod test;
 
+pub use accumulator::UnionAccumulator;
+pub use intersections::find_intersections;
+pub use poly_set::PolySet;
+pub use provenance::EdgeSource;
+pub use stream::{OperationStream, StreamError};
+
 use super::Polygon;
 use crate::utils::UnorientedLine;
 use queue::{Event, Q};
+use sweep_line::{Membership, Sources};
 
 impl Polygon {
     /// Apply an operation to a collection of polygons.
@@ -15,26 +28,203 @@ impl Polygon {
     /// # Correctness
     /// This function has not been directly tested, but is used in the implementation of `Polygon::difference`.
     pub fn operation<'r>(
+        polygons: impl IntoIterator<Item = &'r Self>,
+        inside: fn(&PolySet) -> bool,
+    ) -> Self {
+        sweep_unlimited(polygons, inside)
+    }
+
+    /// Apply an operation to a collection of polygons, like `Polygon::operation`,
+    /// with the set of polygons given to `inside` as a `BitVec`, as `Polygon::operation` did before `PolySet`.
+    ///
+    /// # Correctness
+    /// It is tested against `Polygon::operation` on a few overlapping and self-intersecting polygons.
+    #[deprecated(
+        note = "use `Polygon::operation`, whose `inside` takes a `PolySet` with the same methods"
+    )]
+    pub fn operation_bitvec<'r>(
         polygons: impl IntoIterator<Item = &'r Self>,
         inside: fn(&bit_vec::BitVec) -> bool,
     ) -> Self {
-        // Step 1: Populate the queue.
+        sweep_unlimited(polygons, inside)
+    }
 
-        let mut events = Q::new();
+    /// Apply an operation to a collection of polygons, like `Polygon::operation`,
+    /// but give up once it exceeds the limits, or once `cancelled` returns true.
+    /// `cancelled` is called once per event, so it should be cheap.
+    ///
+    /// ```
+    /// # use polygon3::{Limits, OperationError, PolySet, Polygon};
+    /// # use core::convert::TryInto;
+    /// let triangle = Polygon::try_from_edges(vec![vec![
+    ///     [1, 0, 1].try_into().unwrap(),
+    ///     [0, 1, 1].try_into().unwrap(),
+    ///     [-1, -1, 1].try_into().unwrap(),
+    /// ]])
+    /// .unwrap();
+    ///
+    /// let limits = Limits {
+    ///     max_events: 2,
+    ///     ..Limits::default()
+    /// };
+    /// let result = Polygon::try_operation(&[triangle], PolySet::any, limits, || false);
+    /// assert_eq!(result.unwrap_err(), OperationError::LimitExceeded);
+    /// ```
+    ///
+    /// # Correctness
+    /// This function has not been fuzzed, but uses the same code as `Polygon::operation`.
+    pub fn try_operation<'r>(
+        polygons: impl IntoIterator<Item = &'r Self>,
+        inside: fn(&PolySet) -> bool,
+        limits: Limits,
+        mut cancelled: impl FnMut() -> bool,
+    ) -> Result<Self, OperationError> {
+        sweep(polygons, inside, |num_events, num_output_edges| {
+            if cancelled() {
+                Err(OperationError::Cancelled)
+            } else if num_events > limits.max_events || num_output_edges > limits.max_output_edges {
+                Err(OperationError::LimitExceeded)
+            } else {
+                Ok(())
+            }
+        })
+    }
+}
 
-        let mut polynum = 0;
+/// Bounds on the work done by `Polygon::try_operation`.
+/// The default is unlimited.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Limits {
+    /// The most points the sweep line may stop at, including vertices and crossings.
+    pub max_events: usize,
+    /// The most output edges the sweep may create, counting those in rings it has not finished yet.
+    /// An edge that another edge's vertex or crossing lies on is counted once for each piece between them,
+    /// so this bounds the memory used during the operation, as well as the size of the result.
+    pub max_output_edges: usize,
+}
 
-        polygons.into_iter().for_each(|poly| {
-            events.extend(poly.0.iter().flat_map(|edges| {
-                crate::utils::pairs(&edges).map(|(&e1, &e2)| {
-                    Event::new_vertex(e1.intersect(e2), [(e1, polynum), (e2, polynum)])
-                })
-            }));
-            polynum += 1;
-        });
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_events: usize::MAX,
+            max_output_edges: usize::MAX,
+        }
+    }
+}
+
+/// Why `Polygon::try_operation` gave up.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OperationError {
+    /// The cancellation callback returned true.
+    Cancelled,
+    /// The operation needed more events or output edges than its limits allow.
+    LimitExceeded,
+}
+
+impl std::fmt::Display for OperationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "The polygon operation was cancelled."),
+            Self::LimitExceeded => write!(f, "The polygon operation exceeded its limits."),
+        }
+    }
+}
+impl std::error::Error for OperationError {}
+
+#[cfg(feature = "rayon")]
+use rayon::join;
+
+#[cfg(not(feature = "rayon"))]
+fn join<A, B>(a: impl FnOnce() -> A, b: impl FnOnce() -> B) -> (A, B) {
+    (a(), b())
+}
+
+/// Run the sweep line with no limits.
+fn sweep_unlimited<'r, M: Membership>(
+    polygons: impl IntoIterator<Item = &'r Polygon>,
+    inside: fn(&M) -> bool,
+) -> Polygon {
+    let no_limits = |_, _| Ok::<_, std::convert::Infallible>(());
+    match sweep(polygons, inside, no_limits) {
+        Ok(polygon) => polygon,
+        Err(never) => match never {},
+    }
+}
+
+/// Run the sweep line over a collection of polygons,
+/// tracking which of them each region is in with `M`,
+/// and output the boundary of the regions where `inside` returns true.
+///
+/// After each event, `check` is given the number of events so far, and the number of output edges created so far,
+/// including those in rings that are not finished yet.
+/// If it returns an error, stop and return it.
+fn sweep<'r, M: Membership, E>(
+    polygons: impl IntoIterator<Item = &'r Polygon>,
+    inside: fn(&M) -> bool,
+    mut check: impl FnMut(usize, usize) -> Result<(), E>,
+) -> Result<Polygon, E> {
+    // Step 1: Populate the queue.
 
-        // Step 2: Sweep line.
+    let polygons: Vec<&Polygon> = polygons.into_iter().collect();
+    let mut sweep: Sweep<M> = Sweep::new(polygons.len(), inside, |_| ());
+    for (polynum, poly) in polygons.into_iter().enumerate() {
+        sweep.add_polygon(poly, polynum);
+    }
+
+    // Step 2: Sweep line.
+
+    let mut num_events = 0;
+    while sweep.step() {
+        num_events += 1;
+        check(num_events, sweep.sweep_line.num_out_edges)?;
+    }
+
+    Ok(Polygon(sweep.sweep_line.out))
+}
 
+/// A sweep in progress.
+/// Each output edge is labelled with the input edges along it, with `P`; `()` ignores them.
+struct Sweep<M, L = (), P = ()> {
+    events: Q,
+    sweep_line: sweep_line::SweepLine<M, L, P>,
+    line_endings: Vec<(UnorientedLine, EdgeSource, i32)>,
+}
+
+impl<M: Membership, L: Clone + PartialEq, P: Sources> Sweep<M, L, P> {
+    /// Start a sweep whose output bounds the regions where `inside` is true,
+    /// with a separate set of rings for each label.
+    fn new(num_polys: usize, inside: fn(&M) -> bool, label: fn(&M) -> L) -> Self {
+        Self {
+            events: Q::new(),
+            sweep_line: sweep_line::SweepLine::new(num_polys, inside, label),
+            line_endings: Vec::new(),
+        }
+    }
+
+    /// Queue the vertices of a polygon, as polygon number `polynum`.
+    fn add_polygon(&mut self, poly: &Polygon, polynum: usize) {
+        self.events
+            .extend(poly.0.iter().enumerate().flat_map(|(ring, edges)| {
+                let n = edges.len();
+                let source = move |edge| EdgeSource {
+                    polygon: polynum,
+                    ring,
+                    edge: edge % n,
+                };
+                // Vertices are never at infinity, since `Polygon::try_from_edges` rejects them.
+                crate::utils::pairs(edges)
+                    .enumerate()
+                    .filter_map(move |(i, (&e1, &e2))| {
+                        Event::new_vertex(e1.intersect(e2), [(e1, source(i)), (e2, source(i + 1))])
+                    })
+            }));
+    }
+
+    /// Handle everything at the next point on the sweep line.
+    /// Output rings finished there are pushed onto `sweep_line.out`, their labels onto `sweep_line.out_labels`,
+    /// and the sources of their edges onto `sweep_line.out_sources`.
+    /// Return false if there are no more events.
+    fn step(&mut self) -> bool {
         //  \::::::::::::::::::::::/
         //   \::::::::::::::::::::/
         //    \::::::::/\::::::::/
@@ -50,30 +240,74 @@ impl Polygon {
         //   /%%%%%%%%%%%%%%%%%%%%\
         //  /%%%%%%%%%%%%%%%%%%%%%%\
 
-        let mut sweep_line = sweep_line::SweepLine::new(polynum, inside);
+        let point = match self.events.next_event(&mut self.line_endings) {
+            Some(point) => point,
+            None => return false,
+        };
+        // println!("{:?}", point);
 
-        let mut line_endings: Vec<(UnorientedLine, usize)> = Vec::new();
-        while let Some(point) = events.next_event(&mut line_endings) {
-            // println!("{:?}", point);
+        let mut section = self.sweep_line.relevant_section_reversed(point);
 
-            let mut section = sweep_line.relevant_section_reversed(point);
-
-            line_endings.iter().for_each(|&(line, poly_idx)| {
-                section.insert(line, poly_idx);
+        self.line_endings
+            .iter()
+            .for_each(|&(line, source, winding)| {
+                section.insert(line, source, winding);
             });
 
-            events.extend(
-                section
-                    .boundary_intersections()
-                    .filter(|&pt| {
-                        let [_, _, z]: [i64; 3] = pt.into();
-                        z != 0
-                    })
-                    .map(Event::new_intersection),
-            );
-        }
+        self.events.extend(
+            section
+                .boundary_intersections()
+                .filter_map(Event::new_intersection),
+        );
 
-        Self(sweep_line.out)
+        // Output rings are finished when the section is dropped.
+        true
+    }
+}
+
+/// How to decide which points a self-intersecting polygon contains.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside if a ray from the point crosses the boundary an odd number of times.
+    /// This is the rule used everywhere else in this crate.
+    EvenOdd,
+    /// Inside if the boundary winds around the point a nonzero number of times.
+    NonZero,
+}
+
+impl Polygon {
+    /// Repair a polygon whose rings cross or overlap themselves or each other.
+    /// The result covers the same points (away from the boundary) under the given fill rule,
+    /// and passes `Polygon::validate`.
+    ///
+    /// ```
+    /// # use polygon3::{FillRule, Polygon};
+    /// # use core::convert::TryInto;
+    /// // A bowtie, crossing itself at the origin.
+    /// let bowtie = Polygon::try_from_edges(vec![vec![
+    ///     [0, 1, 7].try_into().unwrap(),
+    ///     [1, 1, 0].try_into().unwrap(),
+    ///     [0, -1, 7].try_into().unwrap(),
+    ///     [1, -1, 0].try_into().unwrap(),
+    /// ]])
+    /// .unwrap();
+    /// assert!(bowtie.validate().is_err());
+    ///
+    /// let repaired = bowtie.make_valid(FillRule::EvenOdd);
+    /// assert!(repaired.validate().is_ok());
+    /// assert_eq!(repaired.num_rings(), 2);
+    /// ```
+    ///
+    /// # Correctness
+    /// This function has not been fuzzed. Its tests compare containment before and after on a grid of points.
+    /// The fuzz target `fuzz_make_valid` checks it through `Polygon::test_make_valid`, but is not enabled by default.
+    pub fn make_valid(&self, fill_rule: FillRule) -> Self {
+        match fill_rule {
+            FillRule::EvenOdd => sweep_unlimited(std::iter::once(self), PolySet::any),
+            FillRule::NonZero => sweep_unlimited(std::iter::once(self), |windings: &Vec<i32>| {
+                windings.iter().any(|&w| w != 0)
+            }),
+        }
     }
 }
 
@@ -83,7 +317,28 @@ impl Polygon {
     /// # Correctness
     /// This function has not been directly tested, but internally uses the same code as `Polygon::difference`.
     pub fn union<'r>(polygons: impl IntoIterator<Item = &'r Self>) -> Self {
-        Self::operation(polygons, bit_vec::BitVec::none)
+        Self::operation(polygons, PolySet::none)
+    }
+
+    /// Take the union of many polygons, by splitting them in half, taking the union of each half, and combining the results.
+    /// This covers the same region as `Polygon::union`, but each sweep only tracks a few polygons,
+    /// so it is much faster when there are thousands of them.
+    ///
+    /// With the `rayon` feature, the halves are computed in parallel.
+    ///
+    /// # Correctness
+    /// This function has not been fuzzed. It is tested against `Polygon::union`.
+    pub fn union_many(polygons: &[Self]) -> Self {
+        // Below this, a single sweep is cheaper than splitting.
+        const LEAF_SIZE: usize = 8;
+
+        if polygons.len() <= LEAF_SIZE {
+            return Self::union(polygons);
+        }
+
+        let (a, b) = polygons.split_at(polygons.len() / 2);
+        let (a, b) = join(|| Self::union_many(a), || Self::union_many(b));
+        Self::union(&[a, b])
     }
 
     /// Take the intersection of a collection of polygons.
@@ -91,7 +346,7 @@ impl Polygon {
     /// # Correctness
     /// This function has not been directly tested, but internally uses the same code as `Polygon::difference`.
     pub fn intersection<'r>(polygons: impl IntoIterator<Item = &'r Self>) -> Self {
-        Self::operation(polygons, bit_vec::BitVec::all)
+        Self::operation(polygons, PolySet::all)
     }
 
     /// Subtract a collection of polygons from a polygon.
@@ -164,4 +419,26 @@ impl Polygon {
             assert_eq!(answer_1, answer_2);
         }
     }
+
+    /// Given a point and a polygon, test if the point is in the polygon under the fill rule,
+    /// and if it is in the repaired polygon.
+    /// Make sure the two answers are the same, and that the repaired polygon is valid.
+    ///
+    /// If the library is bug-free, this always passes.
+    pub fn test_make_valid(self, point: crate::utils::Point, fill_rule: FillRule) {
+        use std::cmp::Ordering;
+        let repaired = self.make_valid(fill_rule);
+        assert!(repaired.validate().is_ok());
+
+        if let Some(winding) = self.winding_number(point) {
+            let answer_1 = match fill_rule {
+                FillRule::EvenOdd if winding % 2 != 0 => Ordering::Greater,
+                FillRule::NonZero if winding != 0 => Ordering::Greater,
+                _ => Ordering::Less,
+            };
+            let answer_2 = repaired.contains(point);
+
+            assert_eq!(answer_1, answer_2);
+        }
+    }
 }
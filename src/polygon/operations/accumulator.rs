@@ -0,0 +1,82 @@
+use super::Polygon;
+
+/// A union of polygons that changes one polygon at a time.
+///
+/// Changes are queued, and applied together the next time the union is needed.
+/// A run of additions is merged with `Polygon::union_many`,
+/// and a run of removals is subtracted with a single `Polygon::difference`,
+/// so the cost of each sweep depends on the current union and the queued changes, not the whole history.
+///
+/// ```
+/// # use polygon3::{Polygon, UnionAccumulator};
+/// # use core::convert::TryInto;
+/// # use std::cmp::Ordering;
+/// let square = |x: i32, y: i32| {
+///     Polygon::try_from_edges(vec![vec![
+///         [1, 0, -x].try_into().unwrap(),
+///         [0, 1, -y].try_into().unwrap(),
+///         [-1, 0, x + 2].try_into().unwrap(),
+///         [0, -1, y + 2].try_into().unwrap(),
+///     ]])
+///     .unwrap()
+/// };
+///
+/// let mut explored = UnionAccumulator::new();
+/// explored.add(&square(0, 0));
+/// explored.add(&square(1, 0));
+/// let point = [5, 1, 2].try_into().unwrap();
+/// assert_eq!(explored.current().contains(point), Ordering::Greater);
+/// explored.remove(&square(2, 0));
+/// assert_eq!(explored.current().contains(point), Ordering::Less);
+/// ```
+///
+/// # Correctness
+/// This type has not been fuzzed. It is tested against applying each change as it arrives.
+#[derive(Debug, Clone, Default)]
+pub struct UnionAccumulator {
+    union: Polygon,
+    added: Vec<Polygon>,
+    removed: Vec<Polygon>,
+}
+
+impl UnionAccumulator {
+    /// An empty union.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a polygon to the union.
+    pub fn add(&mut self, polygon: &Polygon) {
+        if !self.removed.is_empty() {
+            self.flush();
+        }
+        self.added.push(polygon.clone());
+    }
+
+    /// Subtract a polygon from the union.
+    pub fn remove(&mut self, polygon: &Polygon) {
+        if !self.added.is_empty() {
+            self.flush();
+        }
+        self.removed.push(polygon.clone());
+    }
+
+    /// The union of everything added so far, minus everything removed since.
+    pub fn current(&mut self) -> &Polygon {
+        self.flush();
+        &self.union
+    }
+
+    /// Apply the queued changes. At most one of `added` and `removed` is nonempty.
+    fn flush(&mut self) {
+        if !self.added.is_empty() {
+            self.added.push(std::mem::take(&mut self.union));
+            self.union = Polygon::union_many(&self.added);
+            self.added.clear();
+        }
+        if !self.removed.is_empty() {
+            self.union = self.union.difference(&self.removed);
+            self.removed.clear();
+        }
+    }
+}
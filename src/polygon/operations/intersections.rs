@@ -0,0 +1,327 @@
+use super::queue::{positive, priority};
+use super::sweep_line::tree::{Priorities, Tree};
+use super::sweep_line::{angle_key, cmp_line_to_point};
+use crate::segment::Segment;
+use crate::utils::{Point, PointCoord, UnorientedLine};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Find every point where two or more segments meet, using the same sweep line as the polygon operations.
+/// The segments crossing the sweep line are kept in the same balanced tree, so each event takes O(log n) time
+/// plus the number of segments through its point.
+/// Return each such point exactly once, along with the (sorted) indices of all segments passing through it.
+/// The points are all positive, and are returned in sweep-line order (by y, then x).
+///
+/// Touching counts as meeting, so a segment ending on another is reported.
+/// Collinear overlapping segments are reported at the endpoints of the overlap,
+/// and anywhere else they meet other segments.
+///
+/// Segments with an endpoint at infinity are ignored.
+///
+/// ```
+/// # use polygon3::{find_intersections, PointCoord, Segment};
+/// # use core::convert::TryInto;
+/// // The two diagonals of the square from (0, 0) to (2, 2).
+/// let segments = vec![
+///     Segment {
+///         line: [1, -1, 0].try_into().unwrap(),
+///         start: [0, 0, 1].try_into().unwrap(),
+///         end: [2, 2, 1].try_into().unwrap(),
+///     },
+///     Segment {
+///         line: [1, 1, -2].try_into().unwrap(),
+///         start: [0, 2, 1].try_into().unwrap(),
+///         end: [2, 0, 1].try_into().unwrap(),
+///     },
+/// ];
+///
+/// let crossings = find_intersections(segments);
+/// assert_eq!(crossings.len(), 1);
+/// let [x, y, z]: [PointCoord; 3] = crossings[0].0.into();
+/// assert_eq!([x, y], [z, z]);
+/// assert_eq!(crossings[0].1, vec![0, 1]);
+/// ```
+///
+/// # Correctness
+/// This function has not been fuzzed, but is tested against a brute-force search.
+pub fn find_intersections(segments: impl IntoIterator<Item = Segment>) -> Vec<(Point, Vec<usize>)> {
+    let mut lines = Vec::new();
+    let mut ends = Vec::new();
+    let mut events = BinaryHeap::new();
+
+    for (id, segment) in segments.into_iter().enumerate() {
+        lines.push(UnorientedLine(segment.line));
+
+        let (start, end) = match (positive(segment.start), positive(segment.end)) {
+            (Some(a), Some(b)) => {
+                if priority(a) >= priority(b) {
+                    (a, b)
+                } else {
+                    (b, a)
+                }
+            }
+            _ => {
+                ends.push(None);
+                continue;
+            }
+        };
+        ends.push(Some(end));
+
+        events.push(Event {
+            point: start,
+            kind: Kind::Start(id),
+        });
+        events.push(Event {
+            point: end,
+            kind: Kind::End(id),
+        });
+    }
+
+    // The segments crossing the sweep line, from left to right.
+    let mut status: Tree<usize> = Tree::new();
+    let mut priorities = Priorities::new();
+    let mut out = Vec::new();
+
+    let mut starting = Vec::new();
+    let mut ending = Vec::new();
+    while let Some(&event) = events.peek() {
+        let point = event.point;
+
+        starting.clear();
+        ending.clear();
+        while let Some(&e) = events.peek() {
+            if e != event {
+                break;
+            }
+            events.pop();
+            match e.kind {
+                Kind::Start(id) => starting.push(id),
+                Kind::End(id) => ending.push(id),
+                Kind::Crossing => {}
+            }
+        }
+
+        // The segments passing through the point are contiguous in the status.
+        let (left, rest) = std::mem::replace(&mut status, Tree::new())
+            .split(&mut |&id| cmp_line_to_point(lines[id], point) == Ordering::Less);
+        let (middle, right) =
+            rest.split(&mut |&id| cmp_line_to_point(lines[id], point) == Ordering::Equal);
+        let mut passing = Vec::new();
+        middle.drain_into(&mut passing);
+
+        let mut through: Vec<usize> = passing.iter().chain(&starting).copied().collect();
+        through.sort_unstable();
+        through.dedup();
+        if through.len() >= 2 {
+            out.push((point, through));
+        }
+
+        let mut section: Vec<usize> = passing
+            .iter()
+            .chain(&starting)
+            .copied()
+            .filter(|id| !ending.contains(id))
+            .collect();
+        section.sort_by_key(|&id| angle_key(lines[id]));
+
+        // Look for crossings between the new neighbours.
+        let mut check = |a: usize, b: usize| {
+            let crossing = lines[a].intersect(lines[b]);
+            let [_, _, z]: [PointCoord; 3] = crossing.into();
+            if z == 0 || priority(crossing) >= priority(point) {
+                return;
+            }
+            let before_end = |id: usize| match ends[id] {
+                Some(end) => priority(crossing) >= priority(end),
+                None => false,
+            };
+            if before_end(a) && before_end(b) {
+                events.push(Event {
+                    point: crossing,
+                    kind: Kind::Crossing,
+                });
+            }
+        };
+        let l = left.last().copied();
+        let r = right.first().copied();
+        match (section.first(), section.last()) {
+            (Some(&first), Some(&last)) => {
+                if let Some(l) = l {
+                    check(l, first);
+                }
+                if let Some(r) = r {
+                    check(last, r);
+                }
+            }
+            _ => {
+                if let (Some(l), Some(r)) = (l, r) {
+                    check(l, r);
+                }
+            }
+        }
+
+        let mut middle = Tree::new();
+        for id in section {
+            middle = middle.merge(Tree::singleton(id, priorities.next()));
+        }
+        status = left.merge(middle).merge(right);
+    }
+
+    out
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Kind {
+    Start(usize),
+    End(usize),
+    Crossing,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Event {
+    point: Point, // sign positive
+    kind: Kind,
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for Event {}
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> Ordering {
+        priority(self.point).cmp(&priority(other.point))
+    }
+}
+
+#[cfg(test)]
+fn segment(line: [i32; 3], start: [i64; 3], end: [i64; 3]) -> Segment {
+    use std::convert::TryInto;
+    Segment {
+        line: line.try_into().unwrap(),
+        start: start.try_into().unwrap(),
+        end: end.try_into().unwrap(),
+    }
+}
+
+#[cfg(test)]
+fn normalize(p: Point) -> [PointCoord; 3] {
+    let [x, y, z]: [PointCoord; 3] = p.into();
+    let mut g = z;
+    for mut a in [x, y].iter().copied() {
+        while a != 0 {
+            let t = g % a;
+            g = a;
+            a = t;
+        }
+    }
+    let g = g.abs() * z.signum();
+    [x / g, y / g, z / g]
+}
+
+#[test]
+fn test_grid() {
+    // Three horizontal and four vertical segments, all crossing.
+    let mut segments = Vec::new();
+    for y in 0..3 {
+        segments.push(segment([0, 1, -y], [-1, y.into(), 1], [4, y.into(), 1]));
+    }
+    for x in 0..4 {
+        segments.push(segment([1, 0, -x], [x.into(), -1, 1], [x.into(), 3, 1]));
+    }
+
+    let out = find_intersections(segments);
+    assert_eq!(out.len(), 12);
+    for (i, (point, ids)) in out.into_iter().enumerate() {
+        let (y, x) = (i / 4, i % 4);
+        assert_eq!(normalize(point), [x as PointCoord, y as PointCoord, 1]);
+        assert_eq!(ids, vec![y, 3 + x]);
+    }
+}
+
+#[test]
+fn test_star() {
+    // Four segments through the origin, and one touching the end of another.
+    let segments = vec![
+        segment([0, 1, 0], [-2, 0, 1], [2, 0, 1]),
+        segment([1, 0, 0], [0, -2, 1], [0, 2, 1]),
+        segment([1, -1, 0], [-2, -2, 1], [2, 2, 1]),
+        segment([1, 1, 0], [2, -2, 1], [-2, 2, 1]),
+        segment([0, 1, -2], [2, 2, 1], [5, 2, 1]),
+    ];
+
+    let out: Vec<_> = find_intersections(segments)
+        .into_iter()
+        .map(|(p, ids)| (normalize(p), ids))
+        .collect();
+    assert_eq!(
+        out,
+        vec![([0, 0, 1], vec![0, 1, 2, 3]), ([2, 2, 1], vec![2, 4])]
+    );
+}
+
+#[test]
+fn test_brute_force() {
+    use crate::segment::SegmentIntersection;
+    use std::collections::BTreeMap;
+
+    // A small deterministic pseudorandom generator.
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut random = |n: i64| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % n as u64) as i64
+    };
+
+    for _ in 0..100 {
+        let segments: Vec<Segment> = (0..8)
+            .map(|_| {
+                let (x1, y1) = (random(5), random(5));
+                let (x2, y2) = loop {
+                    let p = (random(5), random(5));
+                    if p != (x1, y1) {
+                        break p;
+                    }
+                };
+                let line = [y1 - y2, x2 - x1, x1 * y2 - x2 * y1];
+                let line = [line[0] as i32, line[1] as i32, line[2] as i32];
+                segment(line, [x1, y1, 1], [x2, y2, 1])
+            })
+            .collect();
+
+        let mut candidates = Vec::new();
+        for (i, &s1) in segments.iter().enumerate() {
+            candidates.push(s1.start);
+            candidates.push(s1.end);
+            for &s2 in &segments[..i] {
+                if let Some(SegmentIntersection::Point(p)) = s1.intersection(s2) {
+                    candidates.push(p);
+                }
+            }
+        }
+
+        let mut expected = BTreeMap::new();
+        for p in candidates {
+            let ids: Vec<usize> = (0..segments.len())
+                .filter(|&i| segments[i].contains(p))
+                .collect();
+            if ids.len() >= 2 {
+                expected.insert(normalize(p), ids);
+            }
+        }
+
+        let mut actual = BTreeMap::new();
+        for (p, ids) in find_intersections(segments.iter().copied()) {
+            assert!(actual.insert(normalize(p), ids).is_none());
+        }
+
+        assert_eq!(expected, actual, "{:?}", segments);
+    }
+}
@@ -0,0 +1,111 @@
+use super::{PolySet, Sweep};
+use crate::polygon::Polygon;
+use crate::utils::UnorientedLine;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+impl Polygon {
+    /// Overlay a collection of polygons, each tagged with a payload.
+    ///
+    /// The plane is split into faces, by which of the inputs cover them.
+    /// Each face is given the result of `combine` on the payloads of the inputs that cover it,
+    /// or dropped if `combine` returns None. `combine` is never called for the area outside every input.
+    /// Each returned polygon holds every face covered by the same inputs, so it may have several components.
+    ///
+    /// ```
+    /// # use polygon3::Polygon;
+    /// # use core::convert::TryInto;
+    /// let square = |x: i32, y: i32| {
+    ///     Polygon::try_from_edges(vec![vec![
+    ///         [1, 0, -x].try_into().unwrap(),
+    ///         [0, 1, -y].try_into().unwrap(),
+    ///         [-1, 0, x + 2].try_into().unwrap(),
+    ///         [0, -1, y + 2].try_into().unwrap(),
+    ///     ]])
+    ///     .unwrap()
+    /// };
+    ///
+    /// let zones = [(square(0, 0), "residential"), (square(1, 1), "flood plain")];
+    /// let faces = Polygon::overlay_with(&zones, |tags| Some(tags.len()));
+    /// assert_eq!(faces.len(), 3);
+    ///
+    /// // Merge the faces covered by a single zone.
+    /// let faces = Polygon::dissolve(faces);
+    /// assert_eq!(faces.len(), 2);
+    /// ```
+    ///
+    /// # Correctness
+    /// This function has not been fuzzed. It uses the same sweep as `Polygon::operation`.
+    pub fn overlay_with<T, U>(
+        inputs: &[(Polygon, T)],
+        combine: impl Fn(&[&T]) -> Option<U>,
+    ) -> Vec<(Polygon, U)> {
+        let mut sweep: Sweep<PolySet, PolySet> =
+            Sweep::new(inputs.len(), PolySet::any, PolySet::clone);
+        for (polynum, (poly, _)) in inputs.iter().enumerate() {
+            sweep.add_polygon(poly, polynum);
+        }
+        while sweep.step() {}
+
+        // Group the rings by face, in order of appearance.
+        let sweep_line = sweep.sweep_line;
+        let mut face_numbers: HashMap<PolySet, usize> = HashMap::new();
+        let mut faces: Vec<(Vec<Vec<UnorientedLine>>, PolySet)> = Vec::new();
+        for (ring, set) in sweep_line.out.into_iter().zip(sweep_line.out_labels) {
+            let n = *face_numbers.entry(set.clone()).or_insert(faces.len());
+            if n == faces.len() {
+                faces.push((Vec::new(), set));
+            }
+            faces[n].0.push(ring);
+        }
+
+        faces
+            .into_iter()
+            .filter_map(|(rings, set)| {
+                let payloads: Vec<&T> = set
+                    .iter()
+                    .zip(inputs)
+                    .filter(|&(covered, _)| covered)
+                    .map(|(_, (_, payload))| payload)
+                    .collect();
+                Some((Polygon(rings), combine(&payloads)?))
+            })
+            .collect()
+    }
+
+    /// Merge faces with equal payloads, such as the output of `Polygon::overlay_with`.
+    /// The faces must not overlap.
+    ///
+    /// The result has one polygon per distinct payload, in order of first appearance.
+    ///
+    /// # Correctness
+    /// This function has not been fuzzed. It is a thin wrapper around `Polygon::union_many`.
+    pub fn dissolve<U: Eq + Hash>(
+        faces: impl IntoIterator<Item = (Polygon, U)>,
+    ) -> Vec<(Polygon, U)> {
+        let faces: Vec<(Polygon, U)> = faces.into_iter().collect();
+
+        let mut group_numbers: HashMap<&U, usize> = HashMap::new();
+        let groups: Vec<usize> = faces
+            .iter()
+            .map(|(_, payload)| {
+                let n = group_numbers.len();
+                *group_numbers.entry(payload).or_insert(n)
+            })
+            .collect();
+        let num_groups = group_numbers.len();
+
+        let mut polygons: Vec<Vec<Polygon>> = (0..num_groups).map(|_| Vec::new()).collect();
+        let mut payloads: Vec<Option<U>> = (0..num_groups).map(|_| None).collect();
+        for ((polygon, payload), n) in faces.into_iter().zip(groups) {
+            polygons[n].push(polygon);
+            payloads[n].get_or_insert(payload);
+        }
+
+        polygons
+            .into_iter()
+            .zip(payloads)
+            .filter_map(|(polygons, payload)| Some((Polygon::union_many(&polygons), payload?)))
+            .collect()
+    }
+}
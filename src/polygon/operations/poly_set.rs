@@ -0,0 +1,123 @@
+use super::sweep_line::Membership;
+
+/// A set of polygons, by index: which polygons a region of the plane is in.
+/// This is what the `inside` function of `Polygon::operation` is given.
+///
+/// Sets of up to 64 polygons are stored inline, without allocating.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PolySet {
+    num_polys: usize,
+    words: Words,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Words {
+    Inline(u64),
+    Heap(Vec<u64>),
+}
+
+impl PolySet {
+    /// The number of polygons that could be in the set.
+    pub fn num_polys(&self) -> usize {
+        self.num_polys
+    }
+
+    /// Is polygon `idx` in the set? None if `idx` is out of range.
+    pub fn get(&self, idx: usize) -> Option<bool> {
+        if idx < self.num_polys {
+            Some(self.words()[idx / 64] >> (idx % 64) & 1 == 1)
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over whether each polygon is in the set, in order.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.num_polys).map(move |idx| self.words()[idx / 64] >> (idx % 64) & 1 == 1)
+    }
+
+    /// Is the set empty?
+    pub fn none(&self) -> bool {
+        self.words().iter().all(|&w| w == 0)
+    }
+
+    /// Is any polygon in the set?
+    pub fn any(&self) -> bool {
+        !self.none()
+    }
+
+    /// Is every polygon in the set?
+    pub fn all(&self) -> bool {
+        let words = self.words();
+        let full = self.num_polys / 64;
+        let rest = self.num_polys % 64;
+        words[..full].iter().all(|&w| w == !0) && (rest == 0 || words[full] == (1 << rest) - 1)
+    }
+
+    fn words(&self) -> &[u64] {
+        match &self.words {
+            Words::Inline(w) => std::slice::from_ref(w),
+            Words::Heap(v) => v,
+        }
+    }
+
+    fn words_mut(&mut self) -> &mut [u64] {
+        match &mut self.words {
+            Words::Inline(w) => std::slice::from_mut(w),
+            Words::Heap(v) => v,
+        }
+    }
+}
+
+/// Even-odd membership: which polygons' boundaries have been crossed an odd number of times.
+impl Membership for PolySet {
+    fn empty(num_polys: usize) -> Self {
+        let words = if num_polys <= 64 {
+            Words::Inline(0)
+        } else {
+            Words::Heap(vec![0; num_polys.div_ceil(64)])
+        };
+        Self { num_polys, words }
+    }
+
+    fn add(&mut self, poly_idx: usize, _winding: i32) {
+        self.words_mut()[poly_idx / 64] ^= 1 << (poly_idx % 64);
+    }
+
+    fn cross(&mut self, edge: &Self) {
+        self.words_mut()
+            .iter_mut()
+            .zip(edge.words())
+            .for_each(|(a, b)| *a ^= b);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.none()
+    }
+}
+
+#[test]
+fn test_poly_set() {
+    for &num_polys in &[0, 1, 63, 64, 65, 130] {
+        let mut set = PolySet::empty(num_polys);
+        assert!(set.none());
+        assert_eq!(set.all(), num_polys == 0);
+
+        for idx in 0..num_polys {
+            set.add(idx, 1);
+        }
+        assert!(set.all());
+        assert_eq!(set.any(), num_polys > 0);
+        assert_eq!(set.iter().count(), num_polys);
+        assert_eq!(set.get(num_polys), None);
+
+        if num_polys > 0 {
+            let mut other = PolySet::empty(num_polys);
+            other.add(num_polys - 1, 1);
+            set.cross(&other);
+            assert!(!set.all());
+            assert_eq!(set.get(num_polys - 1), Some(false));
+            assert!(set.iter().take(num_polys - 1).all(|x| x));
+        }
+    }
+}
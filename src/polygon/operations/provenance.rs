@@ -0,0 +1,91 @@
+use super::sweep_line::Sources;
+use super::{PolySet, Sweep};
+use crate::polygon::Polygon;
+
+/// Which input edge an output edge was copied from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EdgeSource {
+    /// The position of the polygon among the inputs.
+    pub polygon: usize,
+    /// The ring within that polygon.
+    pub ring: usize,
+    /// The edge within that ring.
+    pub edge: usize,
+}
+
+impl Polygon {
+    /// Apply an operation to a collection of polygons, like `Polygon::operation`,
+    /// and report where each output edge came from.
+    ///
+    /// The sources are indexed like the output: `sources[ring][edge]` lists the input edges along the output polygon's `edge`th edge in its `ring`th ring.
+    /// Collinear input edges that touch or overlap may be merged into a single output edge, which then lists all of them,
+    /// in order of polygon, then ring, then edge.
+    ///
+    /// ```
+    /// # use polygon3::{EdgeSource, PolySet, Polygon};
+    /// # use core::convert::TryInto;
+    /// let square = |x: i32| {
+    ///     Polygon::try_from_edges(vec![vec![
+    ///         [1, 0, -x].try_into().unwrap(),
+    ///         [0, 1, 0].try_into().unwrap(),
+    ///         [-1, 0, x + 2].try_into().unwrap(),
+    ///         [0, -1, 2].try_into().unwrap(),
+    ///     ]])
+    ///     .unwrap()
+    /// };
+    /// let source = |polygon, edge| EdgeSource { polygon, ring: 0, edge };
+    ///
+    /// // The bottom and top edges of the two squares overlap, so they are merged, and credited to both squares.
+    /// let (_, sources) = Polygon::operation_with_provenance(&[square(0), square(1)], PolySet::any);
+    /// let mut sources: Vec<Vec<EdgeSource>> = sources.into_iter().flatten().collect();
+    /// sources.sort();
+    /// assert_eq!(
+    ///     sources,
+    ///     vec![
+    ///         vec![source(0, 0)],
+    ///         vec![source(0, 1), source(1, 1)],
+    ///         vec![source(0, 3), source(1, 3)],
+    ///         vec![source(1, 2)],
+    ///     ]
+    /// );
+    /// ```
+    ///
+    /// # Correctness
+    /// This function has not been fuzzed. It uses the same sweep as `Polygon::operation`.
+    pub fn operation_with_provenance<'r>(
+        polygons: impl IntoIterator<Item = &'r Self>,
+        inside: fn(&PolySet) -> bool,
+    ) -> (Self, Vec<Vec<Vec<EdgeSource>>>) {
+        let polygons: Vec<&Polygon> = polygons.into_iter().collect();
+        let mut sweep: Sweep<PolySet, (), Vec<EdgeSource>> =
+            Sweep::new(polygons.len(), inside, |_| ());
+        for (polynum, poly) in polygons.into_iter().enumerate() {
+            sweep.add_polygon(poly, polynum);
+        }
+        while sweep.step() {}
+
+        (Polygon(sweep.sweep_line.out), sweep.sweep_line.out_sources)
+    }
+}
+
+/// The input edges along an edge, sorted.
+impl Sources for Vec<EdgeSource> {
+    fn toggle(&mut self, source: EdgeSource) {
+        match self.binary_search(&source) {
+            Ok(n) => {
+                self.remove(n);
+            }
+            Err(n) => self.insert(n, source),
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.extend(other);
+        self.sort_unstable();
+        self.dedup();
+    }
+
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
@@ -1,33 +1,41 @@
 mod priority;
 
+use super::EdgeSource;
 use crate::utils::{Point, UnorientedLine};
-use priority::priority;
+pub(super) use priority::priority;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 
 #[derive(Debug, Copy, Clone)]
 pub(super) struct Event {
     point: Point, // sign positive
-    edges: Option<[(UnorientedLine, usize); 2]>,
+    // The edge arriving at the point, then the edge leaving it, with where they came from.
+    edges: Option<[(UnorientedLine, EdgeSource); 2]>,
 }
 
 impl Event {
-    // Point may not be at infinity.
-    pub fn new_intersection(mut point: Point) -> Self {
-        if point.sign() == Ordering::Less {
-            point = -point
-        };
-        Self { point, edges: None }
+    /// None if the point is at infinity, where the sweep line never reaches.
+    pub fn new_intersection(point: Point) -> Option<Self> {
+        Some(Self {
+            point: positive(point)?,
+            edges: None,
+        })
     }
-    // Point may not be at infinity.
-    pub fn new_vertex(mut point: Point, edges: [(UnorientedLine, usize); 2]) -> Self {
-        if point.sign() == Ordering::Less {
-            point = -point
-        };
-        Self {
-            point,
+    /// None if the point is at infinity, where the sweep line never reaches.
+    pub fn new_vertex(point: Point, edges: [(UnorientedLine, EdgeSource); 2]) -> Option<Self> {
+        Some(Self {
+            point: positive(point)?,
             edges: Some(edges),
-        }
+        })
+    }
+}
+
+/// The positive point at the same place. None if the point is at infinity.
+pub(super) fn positive(point: Point) -> Option<Point> {
+    match point.sign() {
+        Ordering::Greater => Some(point),
+        Ordering::Less => Some(-point),
+        Ordering::Equal => None,
     }
 }
 
@@ -63,6 +71,20 @@ impl Q {
         }
     }
 
+    /// The point of the next event, if any.
+    pub fn peek(&self) -> Option<Point> {
+        self.queue.peek().map(|e| e.point)
+    }
+
+    /// Has the sweep line already passed this (positive) point?
+    pub fn is_past(&self, point: Point) -> bool {
+        match self.last_evt {
+            // The ordering's reversed: max is first
+            Some(e) => priority(e.point) <= priority(point),
+            None => false,
+        }
+    }
+
     /// Push an event, if it is not already in our past.
     pub fn push(&mut self, event: Event) {
         match self.last_evt {
@@ -76,7 +98,11 @@ impl Q {
 
     /// Get everything that happens at the next interesting point on the sweep-line.
     /// Return the point, and put the line endings in `line_endings`.
-    pub fn next_event(&mut self, line_endings: &mut Vec<(UnorientedLine, usize)>) -> Option<Point> {
+    /// Each line ending comes with its winding: -1 if the polygon's boundary arrives at the point along it, and +1 if it leaves.
+    pub fn next_event(
+        &mut self,
+        line_endings: &mut Vec<(UnorientedLine, EdgeSource, i32)>,
+    ) -> Option<Point> {
         line_endings.clear();
         let event = *self.queue.peek()?;
         // Get everything of the same priority.
@@ -86,9 +112,9 @@ impl Q {
             }
             self.queue.pop();
 
-            if let Some([e1, e2]) = e.edges {
-                line_endings.push(e1);
-                line_endings.push(e2);
+            if let Some([(e1, s1), (e2, s2)]) = e.edges {
+                line_endings.push((e1, s1, -1));
+                line_endings.push((e2, s2, 1));
             }
         }
 
@@ -1,31 +1,35 @@
-use crate::utils::Point;
+use crate::utils::{Point, PointCoord, Wide};
 use std::cmp::Ordering;
 
 /// Reversed lexicographic ordering of points.
 /// Larger points are hit first by the sweep line.
+/// Points at infinity are never queued, but would be hit after every finite point.
 pub fn priority(point: Point) -> impl Ord {
-    let [x, y, z]: [i64; 3] = point.into();
-    std::cmp::Reverse((Ratio::new(y, z), Ratio::new(x, z)))
+    let [x, y, z]: [PointCoord; 3] = point.into();
+    Ratio::new(y, z)
+        .zip(Ratio::new(x, z))
+        .map(std::cmp::Reverse)
 }
 
-struct Ratio(i64, i64);
+struct Ratio(PointCoord, PointCoord);
 
 impl Ratio {
-    fn new(a: i64, b: i64) -> Self {
+    /// None if dividing by zero.
+    fn new(a: PointCoord, b: PointCoord) -> Option<Self> {
         match b.cmp(&0) {
-            Ordering::Greater => Self(a, b),
-            Ordering::Less => Self(-a, -b),
-            Ordering::Equal => panic!("Divided by zero"),
+            Ordering::Greater => Some(Self(a, b)),
+            Ordering::Less => Some(Self(-a, -b)),
+            Ordering::Equal => None,
         }
     }
 }
 
 impl Ord for Ratio {
     fn cmp(&self, other: &Self) -> Ordering {
-        let a = i128::from(self.0);
-        let b = i128::from(self.1);
-        let c = i128::from(other.0);
-        let d = i128::from(other.1);
+        let a = Wide::from(self.0);
+        let b = Wide::from(self.1);
+        let c = Wide::from(other.0);
+        let d = Wide::from(other.1);
         (a * d).cmp(&(b * c))
     }
 }
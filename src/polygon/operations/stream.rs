@@ -0,0 +1,157 @@
+use super::queue::priority;
+use super::{PolySet, Sweep};
+use crate::polygon::Polygon;
+use crate::utils::{Line, Point, UnorientedLine};
+use std::cmp::Ordering;
+
+impl Polygon {
+    /// Apply an operation to a stream of polygons, like `Polygon::operation`,
+    /// without holding all of them in memory at once.
+    ///
+    /// Each input is a polygon, along with its index in the `PolySet` given to `inside`, which must be less than `num_polys`.
+    /// Several inputs may share an index, in which case they act as the rings of a single polygon.
+    /// The inputs must be sorted by their lowest vertex, comparing first by y and then by x.
+    ///
+    /// Each input is only pulled from the iterator once the sweep line reaches its lowest vertex,
+    /// and each output ring is yielded as soon as the sweep line has passed it.
+    /// Together, the output rings form the result of the operation.
+    ///
+    /// ```
+    /// # use polygon3::{PolySet, Polygon};
+    /// # use core::convert::TryInto;
+    /// let square = |x: i32, y: i32| {
+    ///     Polygon::try_from_edges(vec![vec![
+    ///         [1, 0, -x].try_into().unwrap(),
+    ///         [0, 1, -y].try_into().unwrap(),
+    ///         [-1, 0, x + 2].try_into().unwrap(),
+    ///         [0, -1, y + 2].try_into().unwrap(),
+    ///     ]])
+    ///     .unwrap()
+    /// };
+    ///
+    /// // Three overlapping squares, stacked vertically.
+    /// let tiles = (0..3).map(|i| (i as usize, square(0, i)));
+    /// let rings: Vec<_> = Polygon::operation_streaming(3, tiles, PolySet::any)
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(rings.len(), 1);
+    /// assert_eq!(rings[0].len(), 4);
+    /// ```
+    ///
+    /// # Correctness
+    /// This function has not been fuzzed. It is tested against `Polygon::operation`.
+    pub fn operation_streaming<I: IntoIterator<Item = (usize, Polygon)>>(
+        num_polys: usize,
+        polygons: I,
+        inside: fn(&PolySet) -> bool,
+    ) -> OperationStream<I::IntoIter> {
+        OperationStream {
+            sweep: Sweep::new(num_polys, inside, |_| ()),
+            num_polys,
+            input: polygons.into_iter().peekable(),
+            num_inputs: 0,
+            ready: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+}
+
+/// The output rings of `Polygon::operation_streaming`, as they are finished.
+pub struct OperationStream<I: Iterator<Item = (usize, Polygon)>> {
+    sweep: Sweep<PolySet>,
+    num_polys: usize,
+    input: std::iter::Peekable<I>,
+    num_inputs: usize,
+    ready: std::vec::IntoIter<Vec<UnorientedLine>>,
+    done: bool,
+}
+
+/// Why `Polygon::operation_streaming` stopped.
+/// `input` is the position of the offending polygon in the input stream.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StreamError {
+    /// The polygon's lowest vertex is below a point the sweep line has already passed.
+    Unsorted { input: usize },
+    /// The polygon's index is not less than `num_polys`.
+    IndexOutOfRange { input: usize },
+}
+
+impl std::fmt::Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Unsorted { input } => write!(
+                f,
+                "Input polygon {} arrived after the sweep line passed its lowest vertex.",
+                input
+            ),
+            Self::IndexOutOfRange { input } => {
+                write!(f, "Input polygon {} has an index out of range.", input)
+            }
+        }
+    }
+}
+impl std::error::Error for StreamError {}
+
+impl<I: Iterator<Item = (usize, Polygon)>> OperationStream<I> {
+    /// Pull in every polygon that starts before the next event.
+    fn load(&mut self) -> Result<(), StreamError> {
+        while let Some((_, poly)) = self.input.peek() {
+            let lowest = lowest_vertex(poly);
+            if let (Some(lowest), Some(next)) = (lowest, self.sweep.events.peek()) {
+                if priority(lowest) < priority(next) {
+                    break;
+                }
+            }
+
+            let input = self.num_inputs;
+            self.num_inputs += 1;
+            let (poly_idx, poly) = match self.input.next() {
+                Some(x) => x,
+                None => break,
+            };
+            if poly_idx >= self.num_polys {
+                return Err(StreamError::IndexOutOfRange { input });
+            }
+            if lowest.is_some_and(|p| self.sweep.events.is_past(p)) {
+                return Err(StreamError::Unsorted { input });
+            }
+            self.sweep.add_polygon(&poly, poly_idx);
+        }
+        Ok(())
+    }
+}
+
+impl<I: Iterator<Item = (usize, Polygon)>> Iterator for OperationStream<I> {
+    type Item = Result<Vec<Line>, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(ring) = self.ready.next() {
+                return Some(Ok(ring.into_iter().map(|l| l.0).collect()));
+            }
+            if self.done {
+                return None;
+            }
+
+            if let Err(e) = self.load() {
+                self.done = true;
+                return Some(Err(e));
+            }
+            if !self.sweep.step() {
+                self.done = true;
+            }
+            self.ready = std::mem::take(&mut self.sweep.sweep_line.out).into_iter();
+            self.sweep.sweep_line.out_labels.clear();
+            self.sweep.sweep_line.out_sources.clear();
+        }
+    }
+}
+
+/// The vertex the sweep line reaches first, if there are any.
+fn lowest_vertex(poly: &Polygon) -> Option<Point> {
+    poly.0
+        .iter()
+        .flat_map(|edges| crate::utils::pairs(edges).map(|(e1, e2)| e1.intersect(*e2)))
+        .filter(|p| p.sign() == Ordering::Greater)
+        .max_by(|&p, &q| priority(p).cmp(&priority(q)))
+}
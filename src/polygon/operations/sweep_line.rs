@@ -1,7 +1,10 @@
 mod chain_end_connector;
+pub(super) mod tree;
 use chain_end_connector::*;
+use tree::{Priorities, Tree};
 
-use crate::utils::{Point, UnorientedLine};
+use super::EdgeSource;
+use crate::utils::{LineCoord, Point, UnorientedLine};
 use bit_vec::BitVec;
 use std::cmp::Ordering;
 
@@ -12,169 +15,295 @@ use std::cmp::Ordering;
 
 // We track the edges that are crossing the sweep-line, and track which regions between are in which polygons.
 
-pub(super) struct SweepLine {
-    edges: Vec<Edge>,
-    regions: Vec<BitVec>,
-    inside: fn(&BitVec) -> bool,
+/// A record of which polygons a region is in, or how an edge changes that.
+pub(crate) trait Membership: Clone {
+    /// In none of the polygons.
+    fn empty(num_polys: usize) -> Self;
+    /// Account for an edge of polygon `poly_idx` starting or ending here.
+    /// `winding` is +1 if the polygon's boundary leaves the vertex along it, and -1 if it arrives.
+    fn add(&mut self, poly_idx: usize, winding: i32);
+    /// Cross an edge, from left to right.
+    fn cross(&mut self, edge: &Self);
+    fn is_empty(&self) -> bool;
+}
+
+/// Even-odd membership: which polygons' boundaries have been crossed an odd number of times.
+/// This is what `Polygon::operation` used before `PolySet`.
+impl Membership for BitVec {
+    fn empty(num_polys: usize) -> Self {
+        BitVec::from_elem(num_polys, false)
+    }
+
+    fn add(&mut self, poly_idx: usize, _winding: i32) {
+        let tmp: bool = self[poly_idx];
+        self.set(poly_idx, !tmp);
+    }
+
+    fn cross(&mut self, edge: &Self) {
+        self.xor(edge);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.none()
+    }
+}
+
+/// Winding numbers, one per polygon.
+impl Membership for Vec<i32> {
+    fn empty(num_polys: usize) -> Self {
+        vec![0; num_polys]
+    }
+
+    fn add(&mut self, poly_idx: usize, winding: i32) {
+        self[poly_idx] += winding;
+    }
+
+    fn cross(&mut self, edge: &Self) {
+        self.iter_mut().zip(edge).for_each(|(a, b)| *a -= b);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.iter().all(|&w| w == 0)
+    }
+}
+
+/// A record of which input edges an edge came from.
+pub(crate) trait Sources: Clone + Default {
+    /// Account for an input edge starting or ending here.
+    /// Every input edge is toggled twice, once at each end.
+    fn toggle(&mut self, source: EdgeSource);
+    /// Add the sources of another piece of the same output edge.
+    fn merge(&mut self, other: Self);
+    fn is_empty(&self) -> bool;
+}
+
+/// Don't keep track of sources.
+impl Sources for () {
+    fn toggle(&mut self, _source: EdgeSource) {}
+
+    fn merge(&mut self, _other: Self) {}
+
+    fn is_empty(&self) -> bool {
+        true
+    }
+}
+
+// The output is the boundary of the regions where `inside` is true.
+// Those regions are further divided by `label`; each output ring bounds a region with a single label.
+pub(super) struct SweepLine<M, L, P> {
+    // The edges crossing the sweep line, from left to right, each with the region just to its left.
+    // The region to the right of every edge is in none of the polygons, since every ring is closed.
+    status: Tree<Slot<M, L, P>>,
+    priorities: Priorities,
+    inside: fn(&M) -> bool,
+    label: fn(&M) -> L,
     pub out: Vec<Vec<UnorientedLine>>,
+    pub out_labels: Vec<L>,
+    pub out_sources: Vec<Vec<P>>,
+    // The number of output edges added to chains so far, including those in unfinished rings.
+    // An edge that the sweep line stops at partway along is counted once for each piece.
+    pub num_out_edges: usize,
     num_polys: usize,
 }
 
-struct Edge {
+struct Slot<M, L, P> {
+    region: M,
+    edge: Edge<M, L, P>,
+}
+
+struct Edge<M, L, P> {
     line: UnorientedLine,
-    polys: BitVec,
-    out_chain_end: Option<chain_end::ChainEnd<UnorientedLine>>,
+    polys: M,
+    // The input edges along this edge. It stays while there are any, even if their polygons cancel out.
+    sources: P,
+    // The unfinished output chains along this edge, bounding the regions to its left and right.
+    out_chain_ends: [Option<(L, OutChainEnd<P>)>; 2],
 }
 
-impl SweepLine {
-    pub fn new(num_polys: usize, inside: fn(&BitVec) -> bool) -> Self {
+impl<M: Membership, L: Clone + PartialEq, P: Sources> SweepLine<M, L, P> {
+    pub fn new(num_polys: usize, inside: fn(&M) -> bool, label: fn(&M) -> L) -> Self {
         Self {
-            edges: Vec::new(),
-            regions: vec![BitVec::from_elem(num_polys, false)],
+            status: Tree::new(),
+            priorities: Priorities::new(),
             inside,
+            label,
             out: Vec::new(),
+            out_labels: Vec::new(),
+            out_sources: Vec::new(),
+            num_out_edges: 0,
             num_polys,
         }
     }
 
-    fn section_reversed(&mut self, range: std::ops::Range<usize>) -> SweepLineSection {
-        let mut relevant_edges: Vec<Edge> = self
-            .edges
-            .splice(range.clone(), std::iter::empty())
-            .rev()
-            .collect();
+    /// The label of a region, or None if it is not inside.
+    fn region_label(&self, region: &M) -> Option<L> {
+        if (self.inside)(region) {
+            Some((self.label)(region))
+        } else {
+            None
+        }
+    }
+
+    pub fn relevant_section_reversed(&mut self, pt: Point) -> SweepLineSection<'_, M, L, P> {
+        let status = std::mem::replace(&mut self.status, Tree::new());
+        let (left, rest) = status
+            .split(&mut |s: &Slot<M, L, P>| cmp_line_to_point(s.edge.line, pt) == Ordering::Less);
+        let (middle, right) = rest
+            .split(&mut |s: &Slot<M, L, P>| cmp_line_to_point(s.edge.line, pt) == Ordering::Equal);
+
+        let mut slots = Vec::new();
+        middle.drain_into(&mut slots);
+
+        let start_region = match (slots.first(), right.first()) {
+            (Some(slot), _) | (None, Some(slot)) => slot.region.clone(),
+            (None, None) => M::empty(self.num_polys),
+        };
+
+        let mut relevant_edges: Vec<Edge<M, L, P>> =
+            slots.into_iter().rev().map(|slot| slot.edge).collect();
         let mut end_connector = ChainEndConnector::new();
 
         relevant_edges
             .iter_mut()
-            .filter_map(|e| e.out_chain_end.take())
-            .for_each(|e| end_connector.end(&mut self.out, e));
+            .flat_map(|e| e.out_chain_ends.iter_mut().filter_map(Option::take))
+            .for_each(|(label, e)| {
+                end_connector.end(
+                    &mut self.out,
+                    &mut self.out_labels,
+                    &mut self.out_sources,
+                    label,
+                    e,
+                )
+            });
 
         SweepLineSection {
             sweep_line: self,
-            range,
+            left,
+            right,
+            start_region,
             relevant_edges,
             end_connector,
         }
     }
+}
 
-    pub fn relevant_section_reversed(&mut self, pt: Point) -> SweepLineSection {
-        let search_fn = |e: &Edge| {
-            let mut line = e.line.0;
-            let [x, _, _]: [i32; 3] = line.into();
-            // Make the line point toward negative x.
-            match x.cmp(&0) {
-                Ordering::Greater => {
-                    line = -line;
-                }
-                Ordering::Equal => {
-                    return Ordering::Equal;
-                }
-                _ => {}
-            }
-            pt.cmp_line(line)
-        };
-
-        let range = match self.edges.binary_search_by(search_fn) {
-            Err(n) => n..n,
-            Ok(n) => {
-                let mut start = n;
-                loop {
-                    if let Some(prev) = start.checked_sub(1) {
-                        if search_fn(&self.edges[prev]) == Ordering::Equal {
-                            start = prev;
-                            continue;
-                        }
-                    }
-                    break;
-                }
-
-                let mut end = n;
-                loop {
-                    end += 1;
-                    if let Some(x) = self.edges.get(end) {
-                        if search_fn(x) == Ordering::Equal {
-                            continue;
-                        }
-                    }
-                    break;
-                }
-
-                start..end
-            }
-        };
-
-        self.section_reversed(range)
+/// Where is a line crossing the sweep line, relative to a point on the sweep line?
+/// `Ordering::Less` means the line is to the left of the point.
+/// Horizontal lines are considered to pass through the point.
+pub(super) fn cmp_line_to_point(line: UnorientedLine, pt: Point) -> Ordering {
+    let mut line = line.0;
+    let [x, _, _]: [LineCoord; 3] = line.into();
+    // Make the line point toward negative x.
+    match x.cmp(&0) {
+        Ordering::Greater => {
+            line = -line;
+        }
+        Ordering::Equal => {
+            return Ordering::Equal;
+        }
+        _ => {}
     }
+    pt.cmp_line(line)
 }
 
-pub(crate) struct SweepLineSection<'r> {
-    sweep_line: &'r mut SweepLine,
-    range: std::ops::Range<usize>,
+/// The order in which lines leave a point on the sweep line, from left to right.
+pub(super) fn angle_key(line: UnorientedLine) -> impl Ord {
+    // \ < | < / < -
+    std::cmp::Reverse(line.angle_from_horizontal())
+}
+
+pub(crate) struct SweepLineSection<'r, M: Membership, L: Clone + PartialEq, P: Sources> {
+    sweep_line: &'r mut SweepLine<M, L, P>,
+    // The parts of the status to either side of the section.
+    left: Tree<Slot<M, L, P>>,
+    right: Tree<Slot<M, L, P>>,
+    // The region just right of `left`.
+    start_region: M,
     // Note: None of the relevant edges will have output chains attached.
-    relevant_edges: Vec<Edge>,
-    end_connector: ChainEndConnector,
+    relevant_edges: Vec<Edge<M, L, P>>,
+    end_connector: ChainEndConnector<L, P>,
 }
 
-impl<'r> Drop for SweepLineSection<'r> {
+impl<'r, M: Membership, L: Clone + PartialEq, P: Sources> Drop for SweepLineSection<'r, M, L, P> {
     fn drop(&mut self) {
         let Self {
             sweep_line,
-            range,
+            left,
+            right,
+            start_region,
             relevant_edges,
             end_connector,
         } = self;
-        let mut relevant_edges: Vec<Edge> = std::mem::replace(relevant_edges, Vec::new());
+        let relevant_edges: Vec<Edge<M, L, P>> = std::mem::take(relevant_edges);
 
-        let mut region: BitVec = sweep_line.regions[range.start].clone();
-        let mut regions: Vec<BitVec> = Vec::new();
-        for edge in &mut relevant_edges {
-            regions.push(region.clone());
+        let mut region: M = start_region.clone();
+        let mut middle = Tree::new();
+        for mut edge in relevant_edges {
+            let slot_region = region.clone();
 
-            let bool1 = (sweep_line.inside)(&region);
-            region.xor(&edge.polys);
-            let bool2 = (sweep_line.inside)(&region);
+            let label1 = sweep_line.region_label(&region);
+            region.cross(&edge.polys);
+            let label2 = sweep_line.region_label(&region);
 
-            if bool1 ^ bool2 {
-                let (e1, e2) = chain_end::ChainEnd::new(std::iter::once(edge.line));
-                end_connector.end(&mut sweep_line.out, e1);
-                edge.out_chain_end = Some(e2);
+            if label1 != label2 {
+                for (label, out_chain_end) in [label1, label2].iter().zip(&mut edge.out_chain_ends)
+                {
+                    if let Some(label) = label {
+                        sweep_line.num_out_edges += 1;
+                        let (e1, e2) =
+                            OutChainEnd::new(std::iter::once((edge.line, edge.sources.clone())));
+                        end_connector.end(
+                            &mut sweep_line.out,
+                            &mut sweep_line.out_labels,
+                            &mut sweep_line.out_sources,
+                            label.clone(),
+                            e1,
+                        );
+                        *out_chain_end = Some((label.clone(), e2));
+                    }
+                }
             }
+
+            let slot = Slot {
+                region: slot_region,
+                edge,
+            };
+            middle = middle.merge(Tree::singleton(slot, sweep_line.priorities.next()));
         }
-        sweep_line.regions.splice(range.clone(), regions);
 
-        sweep_line
-            .edges
-            .splice(range.start..range.start, relevant_edges);
+        let left = std::mem::replace(left, Tree::new());
+        let right = std::mem::replace(right, Tree::new());
+        sweep_line.status = left.merge(middle).merge(right);
     }
 }
 
-impl<'r> SweepLineSection<'r> {
-    pub fn insert(&mut self, line: UnorientedLine, poly_idx: usize) {
-        // \ < | < / < -
-        let search_fn = |l: UnorientedLine| std::cmp::Reverse(l.angle_from_horizontal());
-
+impl<'r, M: Membership, L: Clone + PartialEq, P: Sources> SweepLineSection<'r, M, L, P> {
+    pub fn insert(&mut self, line: UnorientedLine, source: EdgeSource, winding: i32) {
         match self
             .relevant_edges
-            .binary_search_by_key(&search_fn(line), |e| search_fn(e.line))
+            .binary_search_by_key(&angle_key(line), |e| angle_key(e.line))
         {
             Err(n) => {
-                let mut polys = BitVec::from_elem(self.sweep_line.num_polys, false);
-                polys.set(poly_idx, true);
+                let mut polys = M::empty(self.sweep_line.num_polys);
+                polys.add(source.polygon, winding);
+                let mut sources = P::default();
+                sources.toggle(source);
                 self.relevant_edges.insert(
                     n,
                     Edge {
                         line,
                         polys,
-                        out_chain_end: None,
+                        sources,
+                        out_chain_ends: [None, None],
                     },
                 );
             }
             Ok(n) => {
-                let tmp: bool = self.relevant_edges[n].polys[poly_idx];
-                self.relevant_edges[n].polys.set(poly_idx, !tmp);
+                let edge = &mut self.relevant_edges[n];
+                edge.polys.add(source.polygon, winding);
+                edge.sources.toggle(source);
 
-                if self.relevant_edges[n].polys.none() {
+                if edge.polys.is_empty() && edge.sources.is_empty() {
                     self.relevant_edges.remove(n);
                 }
             }
@@ -182,10 +311,8 @@ impl<'r> SweepLineSection<'r> {
     }
 
     pub fn boundary_intersections(&mut self) -> impl Iterator<Item = Point> {
-        let n = self.range.start;
-
-        let edge_00 = n.checked_sub(1).map(|m| &self.sweep_line.edges[m]);
-        let edge_11 = self.sweep_line.edges.get(n);
+        let edge_00 = self.left.last().map(|slot| &slot.edge);
+        let edge_11 = self.right.first().map(|slot| &slot.edge);
 
         let out = if let (Some(edge_01), Some(edge_10)) =
             (self.relevant_edges.first(), self.relevant_edges.last())
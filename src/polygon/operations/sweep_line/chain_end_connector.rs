@@ -1,38 +1,66 @@
+use super::Sources;
 use crate::utils::UnorientedLine;
 use chain_end::*;
 
-pub(crate) struct ChainEndConnector(Option<ChainEnd<UnorientedLine>>);
+/// An end of an unfinished output chain, whose edges carry their sources.
+pub(crate) type OutChainEnd<P> = ChainEnd<(UnorientedLine, P)>;
 
-impl ChainEndConnector {
+/// Joins the chain ends at a point in pairs, separately for each label.
+pub(crate) struct ChainEndConnector<L, P>(Vec<(L, OutChainEnd<P>)>);
+
+impl<L: PartialEq, P: Sources> ChainEndConnector<L, P> {
     pub fn new() -> Self {
-        Self(None)
+        Self(Vec::new())
     }
 
-    pub fn end(&mut self, out: &mut Vec<Vec<UnorientedLine>>, e1: ChainEnd<UnorientedLine>) {
-        if let Some(e2) = self.0.take() {
+    /// If this closes a ring, push it onto `out`, its label onto `out_labels`, and its sources onto `out_sources`.
+    /// Consecutive pieces along the same line are merged into one edge, with the sources of all of them.
+    pub fn end(
+        &mut self,
+        out: &mut Vec<Vec<UnorientedLine>>,
+        out_labels: &mut Vec<L>,
+        out_sources: &mut Vec<Vec<P>>,
+        label: L,
+        e1: OutChainEnd<P>,
+    ) {
+        if let Some(n) = self.0.iter().position(|(l, _)| *l == label) {
+            let (label, e2) = self.0.swap_remove(n);
             if let Some(edges) = e1.connect(e2) {
-                let mut poly = Vec::new();
-                for edge in edges {
-                    if Some(&edge) != poly.last() {
-                        poly.push(edge);
+                let mut poly: Vec<(UnorientedLine, P)> = Vec::new();
+                for (edge, sources) in edges {
+                    match poly.last_mut() {
+                        Some((last, last_sources)) if *last == edge => last_sources.merge(sources),
+                        _ => poly.push((edge, sources)),
                     }
                 }
-                while poly.first() == poly.last() {
-                    poly.pop();
+                while let Some((edge, sources)) = poly.pop() {
+                    match poly.first_mut() {
+                        Some((first, first_sources)) if *first == edge => {
+                            first_sources.merge(sources)
+                        }
+                        Some(_) => {
+                            poly.push((edge, sources));
+                            break;
+                        }
+                        None => break,
+                    }
                 }
                 if !poly.is_empty() {
                     debug_assert!(poly.len() >= 3);
-                    out.push(poly);
+                    let (edges, sources) = poly.into_iter().unzip();
+                    out.push(edges);
+                    out_labels.push(label);
+                    out_sources.push(sources);
                 }
             }
         } else {
-            self.0 = Some(e1);
+            self.0.push((label, e1));
         }
     }
 }
 
-impl Drop for ChainEndConnector {
+impl<L, P> Drop for ChainEndConnector<L, P> {
     fn drop(&mut self) {
-        debug_assert!(self.0.is_none())
+        debug_assert!(self.0.is_empty())
     }
 }
@@ -0,0 +1,183 @@
+// A treap: a binary search tree, balanced by giving each node a random priority,
+// and keeping every node's priority above its children's.
+// We never search by key. Instead, we split a tree wherever a monotone predicate changes,
+// and merge trees end to end. Both take expected O(log n) time.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+#[cfg(not(feature = "vec-status"))]
+pub(crate) type Tree<T> = Treap<T>;
+#[cfg(feature = "vec-status")]
+pub(crate) type Tree<T> = SortedVec<T>;
+
+#[cfg(not(feature = "vec-status"))]
+pub(crate) struct Treap<T>(Option<Box<Node<T>>>);
+
+#[cfg(not(feature = "vec-status"))]
+struct Node<T> {
+    value: T,
+    priority: u64,
+    left: Treap<T>,
+    right: Treap<T>,
+}
+
+#[cfg(not(feature = "vec-status"))]
+impl<T> Treap<T> {
+    pub fn new() -> Self {
+        Self(None)
+    }
+
+    /// A tree holding one value. Priorities should be random.
+    pub fn singleton(value: T, priority: u64) -> Self {
+        Self(Some(Box::new(Node {
+            value,
+            priority,
+            left: Self::new(),
+            right: Self::new(),
+        })))
+    }
+
+    /// Split the tree into the values for which `is_left` is true, and the rest.
+    /// `is_left` must be true for some prefix of the tree, and false afterward.
+    pub fn split(self, is_left: &mut impl FnMut(&T) -> bool) -> (Self, Self) {
+        match self.0 {
+            None => (Self::new(), Self::new()),
+            Some(mut node) => {
+                if is_left(&node.value) {
+                    let (middle, right) = node.right.split(is_left);
+                    node.right = middle;
+                    (Self(Some(node)), right)
+                } else {
+                    let (left, middle) = node.left.split(is_left);
+                    node.left = middle;
+                    (left, Self(Some(node)))
+                }
+            }
+        }
+    }
+
+    /// Join two trees, with every value of `self` before every value of `other`.
+    pub fn merge(self, other: Self) -> Self {
+        match (self.0, other.0) {
+            (None, x) | (x, None) => Self(x),
+            (Some(mut a), Some(mut b)) => {
+                if a.priority >= b.priority {
+                    a.right = a.right.merge(Self(Some(b)));
+                    Self(Some(a))
+                } else {
+                    b.left = Self(Some(a)).merge(b.left);
+                    Self(Some(b))
+                }
+            }
+        }
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        let mut node = self.0.as_ref()?;
+        while let Some(left) = &node.left.0 {
+            node = left;
+        }
+        Some(&node.value)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        let mut node = self.0.as_ref()?;
+        while let Some(right) = &node.right.0 {
+            node = right;
+        }
+        Some(&node.value)
+    }
+
+    /// Push the values onto `out`, in order.
+    pub fn drain_into(self, out: &mut Vec<T>) {
+        if let Some(node) = self.0 {
+            let Node {
+                value, left, right, ..
+            } = *node;
+            left.drain_into(out);
+            out.push(value);
+            right.drain_into(out);
+        }
+    }
+}
+
+/// The status as a sorted `Vec`, as the sweep line stored it before the treap.
+/// Splitting and merging take O(n) time. This is only for comparison in benchmarks.
+#[cfg(feature = "vec-status")]
+pub(crate) struct SortedVec<T>(Vec<T>);
+
+#[cfg(feature = "vec-status")]
+impl<T> SortedVec<T> {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn singleton(value: T, _priority: u64) -> Self {
+        Self(vec![value])
+    }
+
+    pub fn split(mut self, is_left: &mut impl FnMut(&T) -> bool) -> (Self, Self) {
+        let right = self.0.split_off(self.0.partition_point(|x| is_left(x)));
+        (self, Self(right))
+    }
+
+    pub fn merge(mut self, mut other: Self) -> Self {
+        self.0.append(&mut other.0);
+        self
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.0.first()
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.0.last()
+    }
+
+    pub fn drain_into(self, out: &mut Vec<T>) {
+        out.extend(self.0);
+    }
+}
+
+/// A source of pseudorandom priorities (splitmix64).
+///
+/// The seed is random, so that the shape of the treap does not depend only on the input.
+/// Otherwise, input built to match a fixed sequence of priorities could make the treap degenerate.
+pub(crate) struct Priorities(u64);
+
+impl Priorities {
+    pub fn new() -> Self {
+        Self(RandomState::new().build_hasher().finish())
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[test]
+fn test_split_merge() {
+    let mut priorities = Priorities::new();
+    let mut tree = Tree::new();
+    for i in 0..100 {
+        tree = tree.merge(Tree::singleton(i, priorities.next()));
+    }
+    assert_eq!(tree.first(), Some(&0));
+    assert_eq!(tree.last(), Some(&99));
+
+    let (left, right) = tree.split(&mut |&i| i < 40);
+    let (middle, right) = right.split(&mut |&i| i < 60);
+    assert_eq!(left.last(), Some(&39));
+    assert_eq!(middle.first(), Some(&40));
+    assert_eq!(right.first(), Some(&60));
+
+    let mut out = Vec::new();
+    right.drain_into(&mut out);
+    left.merge(middle).drain_into(&mut out);
+    assert_eq!(out, (60..100).chain(0..60).collect::<Vec<_>>());
+}
@@ -38,6 +38,24 @@ fn bowtie() -> Polygon {
     ]])
 }
 
+fn overlapping_squares() -> Polygon {
+    // Two counterclockwise squares in one polygon, overlapping in [-2, 5] x [-2, 5].
+    Polygon(vec![
+        vec![
+            UnorientedLine([1, 0, 5].try_into().unwrap()),
+            UnorientedLine([0, 1, 5].try_into().unwrap()),
+            UnorientedLine([1, 0, -5].try_into().unwrap()),
+            UnorientedLine([0, 1, -5].try_into().unwrap()),
+        ],
+        vec![
+            UnorientedLine([1, 0, 2].try_into().unwrap()),
+            UnorientedLine([0, 1, 2].try_into().unwrap()),
+            UnorientedLine([1, 0, -8].try_into().unwrap()),
+            UnorientedLine([0, 1, -8].try_into().unwrap()),
+        ],
+    ])
+}
+
 #[test]
 fn empty_union() {
     assert!(Polygon::union(std::iter::empty()).0.is_empty());
@@ -172,3 +190,369 @@ fn bowtie_minus_square_and_diamond() {
         }
     }
 }
+
+#[test]
+fn differences_are_valid() {
+    let polygons = vec![square(), diamond(), bowtie()];
+    for a in &polygons {
+        for b in &polygons {
+            assert!(a.difference(std::iter::once(b)).validate().is_ok());
+        }
+        assert!(a.difference(&polygons).validate().is_ok());
+    }
+    assert!(Polygon::union(&polygons).validate().is_ok());
+    assert!(bowtie().validate().is_err());
+}
+
+#[test]
+fn bowtie_make_valid() {
+    for &fill_rule in &[FillRule::EvenOdd, FillRule::NonZero] {
+        for x in -10..=10 {
+            for y in -10..=10 {
+                let point: Point = [x, y, 1].try_into().unwrap();
+
+                println!("{:?}", (x, y, fill_rule));
+                bowtie().test_make_valid(point, fill_rule);
+            }
+        }
+    }
+}
+
+#[test]
+fn overlapping_squares_make_valid() {
+    for &fill_rule in &[FillRule::EvenOdd, FillRule::NonZero] {
+        for x in -10..=10 {
+            for y in -10..=10 {
+                let point: Point = [x, y, 1].try_into().unwrap();
+
+                println!("{:?}", (x, y, fill_rule));
+                overlapping_squares().test_make_valid(point, fill_rule);
+            }
+        }
+    }
+
+    // The overlap is a hole under even-odd, and filled under nonzero.
+    let center: Point = [1, 1, 1].try_into().unwrap();
+    assert_eq!(overlapping_squares().winding_number(center), Some(2));
+    assert_eq!(
+        overlapping_squares()
+            .make_valid(FillRule::EvenOdd)
+            .contains(center),
+        std::cmp::Ordering::Less
+    );
+    assert_eq!(
+        overlapping_squares()
+            .make_valid(FillRule::NonZero)
+            .contains(center),
+        std::cmp::Ordering::Greater
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn bitvec_operation_matches_operation() {
+    let polygons = [square(), diamond(), bowtie()];
+    let pairs: [(fn(&PolySet) -> bool, fn(&bit_vec::BitVec) -> bool); 2] = [
+        (PolySet::any, bit_vec::BitVec::any),
+        (PolySet::all, bit_vec::BitVec::all),
+    ];
+    for &(inside, inside_bitvec) in &pairs {
+        let expected = Polygon::operation(&polygons, inside);
+        let actual = Polygon::operation_bitvec(&polygons, inside_bitvec);
+        assert_eq!(actual.num_edges(), expected.num_edges());
+        assert!(Polygon::operation(&[actual, expected], PolySet::any)
+            .difference(&[Polygon::operation(&polygons, inside)])
+            .0
+            .is_empty());
+    }
+}
+
+#[test]
+fn operation_limits() {
+    let polygons = vec![square(), diamond()];
+    let any = PolySet::any;
+
+    let unlimited = Polygon::try_operation(&polygons, any, Limits::default(), || false).unwrap();
+    assert_eq!(unlimited.0, Polygon::operation(&polygons, any).0);
+    let num_edges = unlimited.num_edges();
+
+    let limits = Limits {
+        max_output_edges: num_edges,
+        ..Limits::default()
+    };
+    assert!(Polygon::try_operation(&polygons, any, limits, || false).is_ok());
+
+    let limits = Limits {
+        max_output_edges: num_edges - 1,
+        ..Limits::default()
+    };
+    assert_eq!(
+        Polygon::try_operation(&polygons, any, limits, || false).unwrap_err(),
+        OperationError::LimitExceeded
+    );
+
+    // The output is a single ring, which is only finished at the last event,
+    // but edges waiting in unfinished chains still count toward the limit.
+    let mut num_events = 0;
+    Polygon::try_operation(&polygons, any, Limits::default(), || {
+        num_events += 1;
+        false
+    })
+    .unwrap();
+    let mut calls = 0;
+    let limits = Limits {
+        max_output_edges: 2,
+        ..Limits::default()
+    };
+    let result = Polygon::try_operation(&polygons, any, limits, || {
+        calls += 1;
+        false
+    });
+    assert_eq!(result.unwrap_err(), OperationError::LimitExceeded);
+    assert_eq!(unlimited.num_rings(), 1);
+    assert!(calls < num_events);
+
+    let limits = Limits {
+        max_events: 3,
+        ..Limits::default()
+    };
+    assert_eq!(
+        Polygon::try_operation(&polygons, any, limits, || false).unwrap_err(),
+        OperationError::LimitExceeded
+    );
+
+    // Cancel partway through.
+    let mut calls = 0;
+    let result = Polygon::try_operation(&polygons, any, Limits::default(), || {
+        calls += 1;
+        calls > 5
+    });
+    assert_eq!(result.unwrap_err(), OperationError::Cancelled);
+    assert_eq!(calls, 6);
+}
+
+#[test]
+fn union_many_matches_union() {
+    // A 7 × 7 grid of overlapping squares, with a diamond and a bowtie.
+    let mut polygons: Vec<Polygon> = (0..7)
+        .flat_map(|i| (0..7).map(move |j| (i, j)))
+        .map(|(i, j)| {
+            Polygon(vec![vec![
+                UnorientedLine([1, 0, -3 * i].try_into().unwrap()),
+                UnorientedLine([0, 1, -3 * j].try_into().unwrap()),
+                UnorientedLine([-1, 0, 3 * i + 4].try_into().unwrap()),
+                UnorientedLine([0, -1, 3 * j + 4].try_into().unwrap()),
+            ]])
+        })
+        .collect();
+    polygons.push(diamond());
+    polygons.push(bowtie());
+
+    let serial = Polygon::union(&polygons);
+    let cascaded = Polygon::union_many(&polygons);
+
+    assert!(cascaded.validate().is_ok());
+    assert_eq!(serial.difference(std::iter::once(&cascaded)).num_edges(), 0);
+    assert_eq!(cascaded.difference(std::iter::once(&serial)).num_edges(), 0);
+    for x in -10..=25 {
+        for y in -10..=25 {
+            let point: Point = [2 * x + 1, 2 * y + 1, 2].try_into().unwrap();
+            assert_eq!(serial.contains(point), cascaded.contains(point));
+        }
+    }
+}
+
+#[test]
+fn streaming_matches_operation() {
+    // Overlapping squares in a staircase, sorted by their lowest vertex, and alternating between two inputs.
+    let squares: Vec<Polygon> = (0..20)
+        .map(|i| {
+            Polygon(vec![vec![
+                UnorientedLine([1, 0, -i].try_into().unwrap()),
+                UnorientedLine([0, 1, -2 * i].try_into().unwrap()),
+                UnorientedLine([-1, 0, i + 3].try_into().unwrap()),
+                UnorientedLine([0, -1, 2 * i + 3].try_into().unwrap()),
+            ]])
+        })
+        .collect();
+    let evens: Vec<Polygon> = squares.iter().step_by(2).cloned().collect();
+    let odds: Vec<Polygon> = squares.iter().skip(1).step_by(2).cloned().collect();
+
+    let inside = |set: &PolySet| set.get(0) == Some(true) && set.get(1) == Some(false);
+    let expected = Polygon::difference(
+        &Polygon::union(&evens),
+        std::iter::once(&Polygon::union(&odds)),
+    );
+
+    let inputs = squares.iter().cloned().enumerate().map(|(i, s)| (i % 2, s));
+    let rings: Vec<Vec<Line>> = Polygon::operation_streaming(2, inputs, inside)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let streamed = Polygon::try_from_edges(rings).unwrap();
+
+    assert!(streamed.validate().is_ok());
+    assert_eq!(
+        streamed.difference(std::iter::once(&expected)).num_edges(),
+        0
+    );
+    assert_eq!(
+        expected.difference(std::iter::once(&streamed)).num_edges(),
+        0
+    );
+}
+
+/// Translate a polygon by (dx, dy).
+fn shifted(polygon: Polygon, dx: LineCoord, dy: LineCoord) -> Polygon {
+    let rings = polygon.0.into_iter().map(|ring| {
+        ring.into_iter()
+            .map(|UnorientedLine(l)| {
+                let [a, b, c]: [LineCoord; 3] = l.into();
+                UnorientedLine([a, b, c - a * dx - b * dy].try_into().unwrap())
+            })
+            .collect()
+    });
+    Polygon(rings.collect())
+}
+
+#[test]
+fn streaming_is_lazy() {
+    use std::cell::Cell;
+
+    // Three squares far apart.
+    let pulled = Cell::new(0);
+    let inputs = (0..3).map(|i| {
+        pulled.set(pulled.get() + 1);
+        (0, shifted(square(), 0, 100 * i))
+    });
+
+    // The stream looks one polygon ahead.
+    let mut stream = Polygon::operation_streaming(1, inputs, PolySet::any);
+    assert!(stream.next().unwrap().is_ok());
+    assert_eq!(pulled.get(), 2);
+    assert!(stream.next().unwrap().is_ok());
+    assert_eq!(pulled.get(), 3);
+    assert!(stream.next().unwrap().is_ok());
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn streaming_errors() {
+    // The diamond reaches lower than the second square, which the sweep line has already passed.
+    let inputs = vec![(0, square()), (0, shifted(square(), 0, 5)), (0, diamond())];
+    let results: Vec<_> = Polygon::operation_streaming(1, inputs, PolySet::any).collect();
+    assert_eq!(results, vec![Err(StreamError::Unsorted { input: 2 })]);
+
+    let inputs = vec![(1, square())];
+    let results: Vec<_> = Polygon::operation_streaming(1, inputs, PolySet::any).collect();
+    assert_eq!(
+        results,
+        vec![Err(StreamError::IndexOutOfRange { input: 0 })]
+    );
+}
+
+#[test]
+fn accumulator_matches_eager() {
+    let squares: Vec<Polygon> = (0..12)
+        .map(|i| shifted(square(), 3 * (i % 4), 4 * (i / 4)))
+        .collect();
+
+    let mut accumulator = UnionAccumulator::new();
+    let mut eager = Polygon::default();
+    for (i, square) in squares.iter().enumerate() {
+        // Every third square carves out of the union, and the rest grow it.
+        if i % 3 == 2 {
+            accumulator.remove(square);
+            eager = eager.difference(std::iter::once(square));
+        } else {
+            accumulator.add(square);
+            eager = Polygon::union(&[eager, square.clone()]);
+        }
+        if i % 2 == 1 {
+            let current = accumulator.current();
+            assert_eq!(current.difference(std::iter::once(&eager)).num_edges(), 0);
+            assert_eq!(eager.difference(std::iter::once(current)).num_edges(), 0);
+        }
+    }
+    assert!(accumulator.current().validate().is_ok());
+}
+
+#[test]
+fn provenance() {
+    let polygons = [square(), diamond(), shifted(square(), 20, 0)];
+    for &inside in &[PolySet::any, PolySet::all, |set: &PolySet| {
+        set.get(0) == Some(true)
+    }] {
+        let (out, sources) = Polygon::operation_with_provenance(&polygons, inside);
+        assert_eq!(
+            out.num_edges(),
+            Polygon::operation(&polygons, inside).num_edges()
+        );
+        assert_eq!(out.0.len(), sources.len());
+        for (ring, ring_sources) in out.0.iter().zip(&sources) {
+            assert_eq!(ring.len(), ring_sources.len());
+            for (&edge, edge_sources) in ring.iter().zip(ring_sources) {
+                assert!(!edge_sources.is_empty());
+                for source in edge_sources {
+                    assert_eq!(edge, polygons[source.polygon].0[source.ring][source.edge]);
+                }
+            }
+        }
+    }
+
+    // The far square is only in the union, and keeps its own edges.
+    let (_, sources) = Polygon::operation_with_provenance(&polygons, PolySet::any);
+    let far: Vec<EdgeSource> = sources
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|source| source.polygon == 2)
+        .collect();
+    assert_eq!(far.len(), 4);
+
+    // The edges of the first two copies cancel out in the sweep, since only parity is kept,
+    // but their sources are still tracked, so every output edge is credited to all three copies.
+    let copies = [square(), square(), square()];
+    let (out, sources) = Polygon::operation_with_provenance(&copies, |set| {
+        set.iter().filter(|&covered| covered).count() % 2 == 1
+    });
+    assert_eq!(out.num_edges(), 4);
+    for edge_sources in sources.into_iter().flatten() {
+        assert_eq!(edge_sources.len(), 3);
+    }
+}
+
+#[test]
+fn overlay_faces() {
+    let inputs = [(square(), 0), (diamond(), 1), (shifted(square(), 6, 0), 2)];
+    let faces = Polygon::overlay_with(&inputs, |tags| {
+        Some(tags.iter().map(|&&t| t).collect::<Vec<u8>>())
+    });
+    assert_eq!(faces.len(), 7);
+
+    let dissolved = Polygon::dissolve(
+        faces
+            .iter()
+            .map(|(face, tags)| (face.clone(), tags.len() > 1)),
+    );
+    assert_eq!(dissolved.len(), 2);
+
+    // Sample points off every boundary.
+    for x in -15..=15 {
+        for y in -15..=15 {
+            let point: Point = [4 * x + 1, 4 * y + 2, 4].try_into().unwrap();
+            let tags: Vec<u8> = inputs
+                .iter()
+                .filter(|(input, _)| input.contains(point) == std::cmp::Ordering::Greater)
+                .map(|&(_, tag)| tag)
+                .collect();
+            for (face, face_tags) in &faces {
+                let inside = face.contains(point) == std::cmp::Ordering::Greater;
+                assert_eq!(inside, *face_tags == tags);
+            }
+            for (face, overlapping) in &dissolved {
+                let inside = face.contains(point) == std::cmp::Ordering::Greater;
+                assert_eq!(inside, !tags.is_empty() && *overlapping == (tags.len() > 1));
+            }
+        }
+    }
+}
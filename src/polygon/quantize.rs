@@ -0,0 +1,351 @@
+use super::{FillRule, Polygon};
+use crate::utils::{power_of_two_scale, Line, LineCoord, LineError, Point, PointCoord};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+/// A map from floating point coordinates onto the integer grid: (x, y) goes to ((x, y) - origin) * scale.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GridTransform {
+    /// The position that maps to the grid's origin.
+    pub origin: [f64; 2],
+    /// Grid units per unit of the original coordinates.
+    pub scale: f64,
+}
+
+impl GridTransform {
+    /// The nearest grid point to a position. None if it is not finite, or does not fit in a `LineCoord`.
+    pub fn snap(&self, [x, y]: [f64; 2]) -> Option<[LineCoord; 2]> {
+        let limit = LineCoord::MAX as f64;
+        let to_grid = |v: f64, o: f64| {
+            let g = ((v - o) * self.scale).round();
+            if g.is_finite() && g.abs() <= limit {
+                Some(g as LineCoord)
+            } else {
+                None
+            }
+        };
+        Some([to_grid(x, self.origin[0])?, to_grid(y, self.origin[1])?])
+    }
+
+    /// Map a point on the grid back to the original coordinates. None if the point is at infinity.
+    pub fn to_f64(&self, point: Point) -> Option<[f64; 2]> {
+        let [x, y] = point.to_f64_rounded()?;
+        Some([
+            x / self.scale + self.origin[0],
+            y / self.scale + self.origin[1],
+        ])
+    }
+}
+
+/// How `Polygon::from_f64_vertices` chooses the grid.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Quantization {
+    /// Center the grid on the bounding box, and use the largest power of two scale
+    /// for which the edges through the snapped vertices are sure to fit.
+    ///
+    /// That keeps every snapped coordinate within about sqrt(`LineCoord::MAX` / 2) of the center:
+    /// 32767 by default, which is only about 16 bits of precision across the bounding box.
+    /// With the `wide-coordinates` feature, the radius is 2^31, for about 32 bits.
+    Auto,
+    /// Use the given grid.
+    Fixed(GridTransform),
+}
+
+/// Why `Polygon::from_f64_vertices` failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum QuantizationError {
+    /// A coordinate was infinite or NaN.
+    NonFinite,
+    /// A vertex was too far from the grid's origin, so it or an edge through it did not fit.
+    OutOfRange,
+    /// The vertices did not make valid edges. Removing redundant vertices is meant to prevent this.
+    Degenerate,
+}
+
+impl std::fmt::Display for QuantizationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::NonFinite => write!(f, "A coordinate was not finite."),
+            Self::OutOfRange => write!(f, "A vertex was too far from the grid's origin."),
+            Self::Degenerate => write!(f, "The vertices did not make valid edges."),
+        }
+    }
+}
+impl std::error::Error for QuantizationError {}
+
+/// Vertices within this distance of the origin, in each coordinate, always have edges that fit in a `Line`.
+/// The edge between (x1, y1) and (x2, y2) has coefficients y1 - y2, x2 - x1, and x1 * y2 - x2 * y1.
+fn safe_radius() -> f64 {
+    ((LineCoord::MAX / 2) as f64).sqrt().floor()
+}
+
+impl Polygon {
+    /// Make a polygon from rings of integer vertices, with an edge between each consecutive pair, wrapping around.
+    ///
+    /// Repeated vertices and vertices where the ring goes straight on, or doubles back on itself, are removed,
+    /// and rings left with fewer than three vertices are dropped.
+    /// The rings may still cross or overlap; `Polygon::make_valid` repairs that.
+    /// The result's vertices come in the same order as the input's, starting from the same vertex if it is kept.
+    ///
+    /// Fails with `QuantizationError::OutOfRange` if an edge's coefficients do not fit in a `LineCoord`.
+    ///
+    /// ```
+    /// # use polygon3::Polygon;
+    /// // A square, with a repeated vertex, and a vertex in the middle of its bottom edge.
+    /// let square = Polygon::from_vertices(vec![vec![[0, 0], [1, 0], [2, 0], [2, 2], [2, 2], [0, 2]]]).unwrap();
+    /// assert_eq!(square.num_edges(), 4);
+    /// assert!(square.validate().is_ok());
+    /// ```
+    pub fn from_vertices(rings: Vec<Vec<[LineCoord; 2]>>) -> Result<Self, QuantizationError> {
+        let rings = rings
+            .into_iter()
+            .map(|ring| simplify(ring.into_iter().map(grid_point).collect()))
+            .filter(|ring| ring.len() >= 3)
+            .map(|ring| {
+                // Edge i runs from vertex i - 1 to vertex i, so edges i and i + 1 meet at vertex i.
+                let previous = ring.iter().cycle().skip(ring.len() - 1);
+                previous
+                    .zip(&ring)
+                    .map(|(&p, &q)| {
+                        Line::through(p, q).map_err(|e| match e {
+                            LineError::Overflow => QuantizationError::OutOfRange,
+                            LineError::Degenerate => QuantizationError::Degenerate,
+                        })
+                    })
+                    .collect::<Result<Vec<Line>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Consecutive vertices are distinct and never collinear, so consecutive edges should meet at a finite point.
+        Self::try_from_edges(rings).map_err(|_| QuantizationError::Degenerate)
+    }
+
+    /// Snap rings of floating point vertices onto the integer grid, and make a valid polygon from them.
+    ///
+    /// Snapping can collapse edges and make rings cross, so the result is repaired with `Polygon::make_valid`,
+    /// using the even-odd rule.
+    /// Returns the grid too, so that results can be mapped back to floating point with `GridTransform::to_f64`.
+    ///
+    /// With `Quantization::Auto`, the grid is only about 65535 units across by default,
+    /// so vertices closer together than about 1/65535 of the bounding box may snap together.
+    /// Enable the `wide-coordinates` feature for about 2^32 units.
+    ///
+    /// ```
+    /// # use polygon3::{Polygon, Quantization};
+    /// let triangle = vec![vec![[10.25, 3.5], [20.0, 3.5], [15.0, 9.125]]];
+    /// let (polygon, grid) = Polygon::from_f64_vertices(&triangle, Quantization::Auto).unwrap();
+    /// assert!(polygon.validate().is_ok());
+    ///
+    /// // These coordinates have few enough bits to survive the round trip exactly.
+    /// let mut vertices: Vec<[f64; 2]> = polygon.vertices()[0]
+    ///     .iter()
+    ///     .map(|&p| grid.to_f64(p).unwrap())
+    ///     .collect();
+    /// vertices.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    /// assert_eq!(vertices, vec![[10.25, 3.5], [15.0, 9.125], [20.0, 3.5]]);
+    /// ```
+    ///
+    /// # Correctness
+    /// This function has not been fuzzed. Its tests check validity, and distance from the input, on a few cases.
+    pub fn from_f64_vertices(
+        rings: &[Vec<[f64; 2]>],
+        quantization: Quantization,
+    ) -> Result<(Self, GridTransform), QuantizationError> {
+        let vertices = rings.iter().flatten();
+        if vertices
+            .clone()
+            .any(|v| !(v[0].is_finite() && v[1].is_finite()))
+        {
+            return Err(QuantizationError::NonFinite);
+        }
+
+        let grid = match quantization {
+            Quantization::Fixed(grid) => grid,
+            Quantization::Auto => {
+                let mut min = [f64::INFINITY; 2];
+                let mut max = [f64::NEG_INFINITY; 2];
+                for v in vertices {
+                    for i in 0..2 {
+                        min[i] = min[i].min(v[i]);
+                        max[i] = max[i].max(v[i]);
+                    }
+                }
+                let origin = [0, 1].map(|i| {
+                    if min[i] <= max[i] {
+                        min[i] + (max[i] - min[i]) / 2.0
+                    } else {
+                        0.0
+                    }
+                });
+                let half = [0, 1]
+                    .iter()
+                    .fold(0.0, |h: f64, &i| h.max((max[i] - min[i]) / 2.0));
+                let scale = if half > 0.0 {
+                    power_of_two_scale(half, safe_radius())
+                } else {
+                    1.0
+                };
+                GridTransform { origin, scale }
+            }
+        };
+
+        let snapped = rings
+            .iter()
+            .map(|ring| {
+                ring.iter()
+                    .map(|&v| grid.snap(v))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(QuantizationError::OutOfRange)?;
+        let polygon = Self::from_vertices(snapped)?;
+        Ok((polygon.make_valid(FillRule::EvenOdd), grid))
+    }
+}
+
+fn grid_point([x, y]: [LineCoord; 2]) -> Point {
+    Point::try_from([PointCoord::from(x), PointCoord::from(y), 1])
+        .expect("widened coordinates are never the minimum")
+}
+
+/// Remove repeated vertices, and vertices collinear with their neighbours.
+fn simplify(ring: Vec<Point>) -> Vec<Point> {
+    // This includes repeated vertices, which are collinear with anything.
+    let redundant = |p: Point, q: Point, r: Point| Point::orient(p, q, r) == Ordering::Equal;
+
+    let mut out: Vec<Point> = Vec::with_capacity(ring.len());
+    for v in ring {
+        out.push(v);
+        // Removing a vertex can make the one before it redundant.
+        while out.len() >= 3
+            && redundant(out[out.len() - 3], out[out.len() - 2], out[out.len() - 1])
+        {
+            out.remove(out.len() - 2);
+        }
+        if out.len() == 2 && out[0] == out[1] {
+            out.pop();
+        }
+    }
+
+    // Then check the vertices around the wrap, from both ends.
+    loop {
+        let n = out.len();
+        if n >= 3 && redundant(out[n - 2], out[n - 1], out[0]) {
+            out.pop();
+        } else if n >= 3 && redundant(out[n - 1], out[0], out[1]) {
+            out.remove(0);
+        } else if n == 2 && out[0] == out[1] {
+            out.pop();
+        } else {
+            break;
+        }
+    }
+    out
+}
+
+#[test]
+fn test_from_vertices() {
+    // A spike, a repeated vertex, and collinear vertices around the wrap.
+    let polygon = Polygon::from_vertices(vec![
+        vec![
+            [1, 0],
+            [2, 0],
+            [2, 2],
+            [2, 5],
+            [2, 2],
+            [0, 2],
+            [0, 0],
+            [0, 0],
+        ],
+        vec![[5, 5], [6, 6], [7, 7]],
+        vec![[9, 9], [9, 9]],
+    ])
+    .unwrap();
+    assert_eq!(polygon.num_rings(), 1);
+    assert_eq!(polygon.num_edges(), 4);
+    assert!(polygon.validate().is_ok());
+    let expected = [[2, 0, 1], [2, 2, 1], [0, 2, 1], [0, 0, 1]]
+        .map(|p| Point::try_from(p.map(PointCoord::from)).unwrap());
+    assert_eq!(polygon.vertices()[0], expected);
+
+    let big = LineCoord::MAX / 2;
+    assert_eq!(
+        Polygon::from_vertices(vec![vec![[big, 1], [0, big], [-big, -big]]]).unwrap_err(),
+        QuantizationError::OutOfRange
+    );
+}
+
+#[test]
+fn test_from_f64_vertices() {
+    // A square with a sliver of a notch, far from the origin.
+    let square = [vec![
+        [1e6, 1e6],
+        [1e6 + 1.0, 1e6],
+        [1e6 + 1.0, 1e6 + 0.5],
+        [1e6 + 0.5, 1e6 + 0.5 + 1e-9],
+        [1e6 + 1.0, 1e6 + 0.5 + 2e-9],
+        [1e6 + 1.0, 1e6 + 1.0],
+        [1e6, 1e6 + 1.0],
+    ]];
+    let (polygon, grid) = Polygon::from_f64_vertices(&square, Quantization::Auto).unwrap();
+    assert!(polygon.validate().is_ok());
+    assert_eq!(grid.origin, [1e6 + 0.5, 1e6 + 0.5]);
+    // The largest power of two that keeps the vertices within the safe radius.
+    assert!(0.5 * grid.scale <= safe_radius() && grid.scale > safe_radius());
+    let half_cell = 0.5 / grid.scale;
+    for p in polygon.vertices().into_iter().flatten() {
+        let [x, y] = grid.to_f64(p).unwrap();
+        for v in [x, y] {
+            assert!(v >= 1e6 - half_cell && v <= 1e6 + 1.0 + half_cell);
+        }
+    }
+
+    // On a coarser grid, the notch collapses into a spike, which is removed.
+    let coarse = GridTransform {
+        origin: [1e6, 1e6],
+        scale: 1024.0,
+    };
+    let (polygon, _) = Polygon::from_f64_vertices(&square, Quantization::Fixed(coarse)).unwrap();
+    assert_eq!(polygon.num_edges(), 4);
+    let mut vertices: Vec<[f64; 2]> = polygon
+        .vertices()
+        .into_iter()
+        .flatten()
+        .map(|p| coarse.to_f64(p).unwrap())
+        .collect();
+    vertices.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(
+        vertices,
+        vec![
+            [1e6, 1e6],
+            [1e6, 1e6 + 1.0],
+            [1e6 + 1.0, 1e6],
+            [1e6 + 1.0, 1e6 + 1.0]
+        ]
+    );
+
+    // Two triangles which snap to touch at a vertex.
+    let unit = GridTransform {
+        origin: [0.0, 0.0],
+        scale: 1.0,
+    };
+    let rings = vec![
+        vec![[0.0, 0.0], [4.0, 0.0], [2.0, 2.4]],
+        vec![[2.1, 1.6], [4.0, 4.0], [0.0, 4.0]],
+    ];
+    let (polygon, _) = Polygon::from_f64_vertices(&rings, Quantization::Fixed(unit)).unwrap();
+    assert!(polygon.validate().is_ok());
+    assert_eq!(polygon.num_edges(), 6);
+
+    assert_eq!(
+        Polygon::from_f64_vertices(&[vec![[0.0, f64::NAN]]], Quantization::Auto).unwrap_err(),
+        QuantizationError::NonFinite
+    );
+    let fine = GridTransform {
+        origin: [0.0, 0.0],
+        scale: 1e300,
+    };
+    assert_eq!(
+        Polygon::from_f64_vertices(&square, Quantization::Fixed(fine)).unwrap_err(),
+        QuantizationError::OutOfRange
+    );
+}
@@ -0,0 +1,92 @@
+use super::Polygon;
+use crate::segment::Segment;
+use crate::utils::{Point, UnorientedLine};
+
+/// One boundary ring of a polygon, borrowed from it.
+#[derive(Debug, Copy, Clone)]
+pub struct Ring<'r>(&'r [UnorientedLine]);
+
+impl Polygon {
+    /// Iterate over the boundary rings of the polygon, without consuming it.
+    pub fn rings(&self) -> impl Iterator<Item = Ring<'_>> {
+        self.0.iter().map(|edges| Ring(edges))
+    }
+
+    /// The number of boundary rings.
+    pub fn num_rings(&self) -> usize {
+        self.0.len()
+    }
+
+    /// The total number of edges, over all rings.
+    pub fn num_edges(&self) -> usize {
+        self.0.iter().map(Vec::len).sum()
+    }
+}
+
+impl<'r> Ring<'r> {
+    /// The number of edges in the ring. This is also the number of vertices.
+    pub fn num_edges(self) -> usize {
+        self.0.len()
+    }
+
+    /// Iterate over the edges of the ring, in order.
+    /// Each edge starts where the previous one ends.
+    ///
+    /// The orientation of each edge's `line` is the one it was created with, and says nothing about which side is inside.
+    pub fn edges(self) -> impl Iterator<Item = Segment> + 'r {
+        let edges = self.0;
+        let prev = edges.iter().cycle().skip(edges.len() - 1);
+        let next = edges.iter().cycle().skip(1);
+
+        prev.zip(edges).zip(next).map(|((&e0, &e1), &e2)| Segment {
+            line: e1.0,
+            start: e0.intersect(e1),
+            end: e1.intersect(e2),
+        })
+    }
+
+    /// Iterate over the vertices of the ring, in order.
+    /// The `i`th vertex is the end of the `i`th edge, and the start of the next.
+    pub fn vertices(self) -> impl Iterator<Item = Point> + 'r {
+        crate::utils::pairs(self.0).map(|(e1, e2)| e1.intersect(*e2))
+    }
+}
+
+#[test]
+fn test_square() {
+    use crate::utils::PointCoord;
+    use crate::ConvexPolygon;
+    use std::convert::TryInto;
+
+    let boundaries = vec![
+        [1, 0, 1].try_into().unwrap(),
+        [-1, 0, 1].try_into().unwrap(),
+        [0, 1, 1].try_into().unwrap(),
+        [0, -1, 1].try_into().unwrap(),
+    ];
+    let square: Polygon = ConvexPolygon::from_boundaries(boundaries)
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    assert_eq!(square.num_rings(), 1);
+    assert_eq!(square.num_edges(), 4);
+
+    let ring = square.rings().next().unwrap();
+    let vertices: Vec<[PointCoord; 3]> = ring.vertices().map(Into::into).collect();
+    let expected: Vec<[PointCoord; 3]> = square.clone().vertices()[0]
+        .iter()
+        .map(|&v| v.into())
+        .collect();
+    assert_eq!(vertices, expected);
+
+    let n = vertices.len();
+    for (i, edge) in ring.edges().enumerate() {
+        let start: [PointCoord; 3] = edge.start.into();
+        let end: [PointCoord; 3] = edge.end.into();
+        assert_eq!(start, vertices[(i + n - 1) % n]);
+        assert_eq!(end, vertices[i]);
+        assert_eq!(edge.start.cmp_line(edge.line), std::cmp::Ordering::Equal);
+        assert_eq!(edge.end.cmp_line(edge.line), std::cmp::Ordering::Equal);
+    }
+}
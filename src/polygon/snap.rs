@@ -0,0 +1,210 @@
+use super::{find_intersections, Polygon};
+use crate::segment::Segment;
+use crate::utils::{LineCoord, OverflowError, Point, PointCoord, Wider};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::num::NonZeroU32;
+
+impl Polygon {
+    /// Round the boundary onto the points of a grid with spacing `grid`, without creating new crossings.
+    ///
+    /// This is snap rounding. Each grid point has a pixel, the `grid` by `grid` square centered on it,
+    /// including its left and bottom sides but not its right and top, so that halves round up.
+    /// A pixel is hot if it contains a vertex, or a point where edges meet or cross.
+    /// Each edge is replaced by a path through the centers of the hot pixels it passes through, in order.
+    ///
+    /// So every output vertex is within half a grid cell of the original boundary, in each coordinate.
+    /// Rounded edges only cross at hot pixel centers, so no crossings appear that the original did not have,
+    /// but nearby edges can collapse onto each other. Rings that collapse to fewer than three vertices are dropped.
+    /// The output is in the form `Polygon::from_vertices` takes, which removes repeated and collinear vertices.
+    ///
+    /// Fails if a grid point the output needs does not fit in a `LineCoord`.
+    /// ```
+    /// # use polygon3::Polygon;
+    /// # use std::num::NonZeroU32;
+    /// // A self-crossing ring. The crossing at (1.5, 1.5) becomes a vertex at (2, 2).
+    /// let bowtie = Polygon::from_vertices(vec![vec![[0, 0], [3, 3], [3, 0], [0, 3]]]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     bowtie.snap_round(NonZeroU32::new(1).unwrap()).unwrap(),
+    ///     vec![vec![[0, 0], [2, 2], [3, 3], [3, 0], [2, 2], [0, 3]]]
+    /// );
+    /// ```
+    ///
+    /// # Correctness
+    /// This function has not been fuzzed. Its tests check a few cases by hand.
+    pub fn snap_round(&self, grid: NonZeroU32) -> Result<Vec<Vec<[LineCoord; 2]>>, OverflowError> {
+        let grid = Wider::from(i64::from(grid.get()));
+
+        let edges: Vec<Segment> = self.rings().flat_map(|ring| ring.edges()).collect();
+        let meetings = find_intersections(edges.iter().copied());
+        let mut hot = edges
+            .iter()
+            .map(|edge| edge.start)
+            .chain(meetings.into_iter().map(|(point, _)| point))
+            .map(|point| center(grid, positive(point)))
+            .collect::<Result<Vec<_>, _>>()?;
+        hot.sort_unstable();
+        hot.dedup();
+
+        let mut rings = Vec::with_capacity(self.num_rings());
+        for ring in self.rings() {
+            // Start with the edge ending at the first vertex, so the output starts at the same vertex.
+            let mut vertices = Vec::new();
+            for edge in ring.edges().skip(1).chain(ring.edges().take(1)) {
+                let mut path = route(grid, edge, &hot)?;
+                // The last pixel is the first of the next edge.
+                path.pop();
+                vertices.extend(path);
+            }
+            if vertices.len() >= 3 {
+                rings.push(vertices);
+            }
+        }
+        Ok(rings)
+    }
+}
+
+fn positive(point: Point) -> [Wider; 3] {
+    let [x, y, z]: [PointCoord; 3] = point.into();
+    let p = [x, y, z].map(Wider::from);
+    if z < 0 {
+        p.map(|c| -c)
+    } else {
+        p
+    }
+}
+
+/// The center of the pixel containing a positive finite point.
+fn center(grid: Wider, [x, y, z]: [Wider; 3]) -> Result<[LineCoord; 2], OverflowError> {
+    let two = Wider::from(2);
+    // The pixel's index is floor((x / z) / grid + 1/2).
+    let snap = |c: Wider| {
+        let (n, d) = (two * c + grid * z, two * grid * z);
+        let q = n / d;
+        let index = if n % d < Wider::from(0) {
+            q - Wider::from(1)
+        } else {
+            q
+        };
+        LineCoord::try_from(index * grid)
+    };
+    Ok([snap(x)?, snap(y)?])
+}
+
+/// The centers of the hot pixels a segment passes through, from its start to its end.
+fn route(
+    grid: Wider,
+    segment: Segment,
+    hot: &[[LineCoord; 2]],
+) -> Result<Vec<[LineCoord; 2]>, OverflowError> {
+    let (p, q) = (positive(segment.start), positive(segment.end));
+    let (start, end) = (center(grid, p)?, center(grid, q)?);
+
+    // The hot pixels are sorted by column, and the segment only passes through the columns between its ends.
+    let first = hot.partition_point(|c| c[0] < start[0].min(end[0]));
+    let last = hot.partition_point(|c| c[0] <= start[0].max(end[0]));
+    let mut path: Vec<[LineCoord; 2]> = hot[first..last]
+        .iter()
+        .copied()
+        .filter(|&c| passes_through(grid, p, q, c))
+        .collect();
+
+    // Both the column and the row change monotonically along the segment.
+    let direction = [0, 1].map(|i| (p[i] * q[2]).cmp(&(q[i] * p[2])));
+    let along = |i: usize, a: LineCoord, b: LineCoord| match direction[i] {
+        Ordering::Greater => b.cmp(&a),
+        _ => a.cmp(&b),
+    };
+    path.sort_by(|a, b| along(0, a[0], b[0]).then(along(1, a[1], b[1])));
+    Ok(path)
+}
+
+/// Does the segment from `p` to `q` pass through the pixel centered on `c`?
+///
+/// Doubling every coordinate puts the pixel's sides on integers.
+/// The open right and top sides are handled by moving them in by an infinitesimal amount.
+fn passes_through(grid: Wider, p: [Wider; 3], q: [Wider; 3], c: [LineCoord; 2]) -> bool {
+    let two = Wider::from(2);
+    let zero = Wider::from(0);
+    let low = c.map(|c| two * Wider::from(c) - grid);
+    let high = c.map(|c| two * Wider::from(c) + grid);
+
+    // Is the doubled coordinate below the bound?
+    let below = |point: [Wider; 3], i: usize, bound: Wider| two * point[i] < bound * point[2];
+    for i in 0..2 {
+        let reaches_high = below(p, i, high[i]) || below(q, i, high[i]);
+        let reaches_low = !below(p, i, low[i]) || !below(q, i, low[i]);
+        if !(reaches_high && reaches_low) {
+            return false;
+        }
+    }
+
+    // Otherwise, the line through the segment must not have every corner strictly on one side.
+    let a = p[1] * q[2] - p[2] * q[1];
+    let b = p[2] * q[0] - p[0] * q[2];
+    let line = two * (p[0] * q[1] - p[1] * q[0]);
+    let sides = [(false, false), (true, false), (false, true), (true, true)].map(|(right, top)| {
+        let x = if right { high[0] } else { low[0] };
+        let y = if top { high[1] } else { low[1] };
+        let value = a * x + b * y + line;
+        // Moving the right side in by ε changes the value by -aε, and moving the top side in changes it by -bε.
+        let nudge = [(right, a), (top, b)]
+            .iter()
+            .filter(|&&(moved, _)| moved)
+            .fold(zero, |sum, &(_, coefficient)| sum - coefficient);
+        value.cmp(&zero).then(nudge.cmp(&zero))
+    });
+    !(sides.iter().all(|&s| s == Ordering::Less) || sides.iter().all(|&s| s == Ordering::Greater))
+}
+
+#[test]
+fn test_snap_round() {
+    use std::convert::TryInto;
+    let grid = |spacing| NonZeroU32::new(spacing).unwrap();
+
+    // A vertex of the hole is close enough to the outer ring to pull it in.
+    let square = vec![[0, 0], [40, 0], [40, 40], [0, 40]];
+    let hole = vec![[21, 1], [30, 10], [12, 10]];
+    let polygon = Polygon::from_vertices(vec![square, hole]).unwrap();
+    let rounded = polygon.snap_round(grid(4)).unwrap();
+    assert_eq!(
+        rounded,
+        vec![
+            vec![[0, 0], [20, 0], [40, 0], [40, 40], [0, 40]],
+            vec![[20, 0], [32, 12], [12, 12]],
+        ]
+    );
+    assert!(Polygon::from_vertices(rounded).is_ok());
+
+    // Fractional vertices, from y >= 0, 2x + y <= 5 and y <= 3x, which are (2.5, 0), (1, 3) and (0, 0).
+    let lines: Vec<[i32; 3]> = vec![[0, 1, 0], [-2, -1, 5], [3, -1, 0]];
+    let triangle = Polygon::try_from_edges(vec![lines
+        .into_iter()
+        .map(|l| l.try_into().unwrap())
+        .collect()])
+    .unwrap();
+    assert_eq!(
+        triangle.snap_round(grid(1)).unwrap(),
+        vec![vec![[3, 0], [1, 3], [0, 0]]]
+    );
+
+    // On a coarse grid, everything collapses.
+    assert_eq!(triangle.snap_round(grid(16)), Ok(vec![]));
+
+    // Pixels are half-open: the bottom edge, along y = 1, misses the hot pixel below it, which ends at y = 1.
+    let strip = vec![[0, 1], [4, 1], [4, 4], [0, 4]];
+    let below = vec![[2, 0], [6, -8], [-2, -8]];
+    let polygon = Polygon::from_vertices(vec![strip, below]).unwrap();
+    assert_eq!(
+        polygon.snap_round(grid(2)).unwrap(),
+        vec![
+            vec![[0, 2], [4, 2], [4, 4], [0, 4]],
+            vec![[2, 0], [6, -8], [-2, -8]],
+        ]
+    );
+
+    let far = LineCoord::MAX;
+    let wide = Polygon::from_vertices(vec![vec![[0, 0], [far, 0], [0, 1]]]).unwrap();
+    assert_eq!(wide.snap_round(grid(4)), Err(OverflowError));
+}
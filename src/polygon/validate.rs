@@ -0,0 +1,379 @@
+use super::Polygon;
+use crate::segment::{Segment, SegmentIntersection};
+use crate::utils::{LineCoord, Point, PointCoord, UnorientedLine};
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+/// A problem found by `Polygon::validate`.
+///
+/// Rings are numbered in the order of `Polygon::rings`, and edges in the order of `Ring::edges`.
+#[derive(Debug, Copy, Clone)]
+pub enum ValidityIssue {
+    /// Two parts of the same ring cross at `point`.
+    /// `edges` holds one edge of the ring through the point from each part.
+    SelfIntersection {
+        ring: usize,
+        edges: [usize; 2],
+        point: Point,
+    },
+    /// Two different rings cross at `point`.
+    /// `edges[i]` is an edge of `rings[i]` through the point.
+    RingCrossing {
+        rings: [usize; 2],
+        edges: [usize; 2],
+        point: Point,
+    },
+    /// Two edges lie on the same line, and have the same endpoints.
+    /// `edges[i]` is an edge of `rings[i]`.
+    DuplicateEdges {
+        rings: [usize; 2],
+        edges: [usize; 2],
+    },
+    /// Two edges lie on the same line, and overlap along a segment of positive length.
+    /// `edges[i]` is an edge of `rings[i]`.
+    CollinearEdges {
+        rings: [usize; 2],
+        edges: [usize; 2],
+    },
+    /// Every vertex of the ring lies on a single line, so the ring encloses no area.
+    ZeroAreaRing { ring: usize },
+}
+
+impl Polygon {
+    /// Check that the polygon's rings are simple, and don't cross or overlap each other.
+    ///
+    /// Rings may touch themselves or each other at isolated points, as long as they don't cross there.
+    /// The outputs of the boolean operations always pass.
+    ///
+    /// ```
+    /// # use polygon3::{Polygon, ValidityIssue};
+    /// # use core::convert::TryInto;
+    /// // A bowtie, crossing itself at the origin.
+    /// let bowtie = Polygon::try_from_edges(vec![vec![
+    ///     [0, 1, 7].try_into().unwrap(),
+    ///     [1, 1, 0].try_into().unwrap(),
+    ///     [0, -1, 7].try_into().unwrap(),
+    ///     [1, -1, 0].try_into().unwrap(),
+    /// ]])
+    /// .unwrap();
+    ///
+    /// match bowtie.validate().unwrap_err()[..] {
+    ///     [ValidityIssue::SelfIntersection { ring: 0, edges: [1, 3], .. }] => {}
+    ///     ref issues => panic!("{:?}", issues),
+    /// }
+    /// ```
+    ///
+    /// # Correctness
+    /// This function has not been fuzzed. It relies on `find_intersections`, which is tested against a brute-force search.
+    pub fn validate(&self) -> Result<(), Vec<ValidityIssue>> {
+        let mut issues = Vec::new();
+
+        let mut ids = Vec::new();
+        let mut segments = Vec::new();
+        for (r, ring) in self.rings().enumerate() {
+            let base = segments.len();
+            for (e, segment) in ring.edges().enumerate() {
+                ids.push((r, e));
+                segments.push(segment);
+            }
+            if is_zero_area(&segments[base..]) {
+                issues.push(ValidityIssue::ZeroAreaRing { ring: r });
+            }
+        }
+
+        let mut overlaps = BTreeSet::new();
+
+        for (point, through) in super::find_intersections(segments.iter().copied()) {
+            // Overlapping edges.
+            for (i, &a) in through.iter().enumerate() {
+                for &b in &through[..i] {
+                    let (s1, s2) = (segments[b], segments[a]);
+                    if UnorientedLine(s1.line) != UnorientedLine(s2.line)
+                        || overlaps.contains(&(b, a))
+                    {
+                        continue;
+                    }
+                    if let Some(SegmentIntersection::Segment(_)) = s1.intersection(s2) {
+                        overlaps.insert((b, a));
+                        let rings = [ids[b].0, ids[a].0];
+                        let edges = [ids[b].1, ids[a].1];
+                        let duplicate = (same(s1.start, s2.start) && same(s1.end, s2.end))
+                            || (same(s1.start, s2.end) && same(s1.end, s2.start));
+                        issues.push(if duplicate {
+                            ValidityIssue::DuplicateEdges { rings, edges }
+                        } else {
+                            ValidityIssue::CollinearEdges { rings, edges }
+                        });
+                    }
+                }
+            }
+
+            // Crossings.
+            let passages: Vec<Passage> = through
+                .iter()
+                .filter_map(|&id| {
+                    let (r, e) = ids[id];
+                    // The ring's edges are contiguous in `segments`.
+                    let ring = &segments[id - e..][..self.0[r].len()];
+                    passage(point, r, e, ring)
+                })
+                .collect();
+
+            for (i, a) in passages.iter().enumerate() {
+                for b in &passages[..i] {
+                    if !b.crosses(a) {
+                        continue;
+                    }
+                    issues.push(if a.ring == b.ring {
+                        ValidityIssue::SelfIntersection {
+                            ring: a.ring,
+                            edges: [b.edge.min(a.edge), b.edge.max(a.edge)],
+                            point,
+                        }
+                    } else {
+                        ValidityIssue::RingCrossing {
+                            rings: [b.ring, a.ring],
+                            edges: [b.edge, a.edge],
+                            point,
+                        }
+                    });
+                }
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+}
+
+/// Are two finite points equal?
+fn same(p: Point, q: Point) -> bool {
+    p.position()
+        .map(|p| Some(p) == q.position())
+        .unwrap_or(false)
+}
+
+/// Does every vertex lie on a single line?
+fn is_zero_area(ring: &[Segment]) -> bool {
+    match ring.iter().find(|s| !same(s.start, s.end)) {
+        Some(s) => ring
+            .iter()
+            .all(|t| t.end.cmp_line(s.line) == Ordering::Equal),
+        None => true,
+    }
+}
+
+/// A direction, as a nonzero vector.
+#[derive(Debug, Copy, Clone)]
+struct Direction(PointCoord, PointCoord);
+
+impl Direction {
+    /// The direction from `from` to `to`, which are distinct points on `segment`'s line.
+    fn along(segment: Segment, from: Point, to: Point) -> Self {
+        let [a, b, _]: [LineCoord; 3] = segment.line.into();
+        let (a, b) = (PointCoord::from(a), PointCoord::from(b));
+        // A direction along the line, pointing toward increasing position.
+        let (dx, dy) = if -a > 0 || (a == 0 && b > 0) {
+            (b, -a)
+        } else {
+            (-b, a)
+        };
+        if from.position() < to.position() {
+            Self(dx, dy)
+        } else {
+            Self(-dx, -dy)
+        }
+    }
+
+    /// Sort key for the counterclockwise angle from the positive x axis.
+    fn half(self) -> bool {
+        !(self.1 > 0 || (self.1 == 0 && self.0 > 0))
+    }
+
+    fn cmp_angle(self, other: Self) -> Ordering {
+        self.half().cmp(&other.half()).then_with(|| {
+            let cross = self.0 * other.1 - self.1 * other.0;
+            0.cmp(&cross)
+        })
+    }
+}
+
+/// One pass of a ring through a point: it arrives from one direction, and leaves in another.
+#[derive(Debug, Copy, Clone)]
+struct Passage {
+    ring: usize,
+    edge: usize,
+    directions: [Direction; 2],
+}
+
+impl Passage {
+    /// Do the two passages cross each other, rather than just touching?
+    /// Passages that share a direction overlap, and are not considered to cross.
+    fn crosses(&self, other: &Self) -> bool {
+        let mut all = [
+            (self.directions[0], true),
+            (self.directions[1], true),
+            (other.directions[0], false),
+            (other.directions[1], false),
+        ];
+        all.sort_by(|a, b| a.0.cmp_angle(b.0));
+        if all
+            .windows(2)
+            .any(|w| w[0].0.cmp_angle(w[1].0) == Ordering::Equal)
+        {
+            return false;
+        }
+        all[0].1 == all[2].1
+    }
+}
+
+/// If `point` is in the interior of edge `e`, or is where edge `e` ends, describe the ring's passage through it.
+/// Edges of zero length are skipped over.
+fn passage(point: Point, r: usize, e: usize, ring: &[Segment]) -> Option<Passage> {
+    let segment = ring[e];
+    let at_start = same(segment.start, point);
+    let at_end = same(segment.end, point);
+
+    let directions = match (at_start, at_end) {
+        (false, false) => [
+            Direction::along(segment, point, segment.start),
+            Direction::along(segment, point, segment.end),
+        ],
+        (false, true) => {
+            let next = ring[e + 1..]
+                .iter()
+                .chain(&ring[..e])
+                .find(|s| !same(s.start, s.end))?;
+            [
+                Direction::along(segment, point, segment.start),
+                Direction::along(*next, point, next.end),
+            ]
+        }
+        (true, _) => return None,
+    };
+
+    Some(Passage {
+        ring: r,
+        edge: e,
+        directions,
+    })
+}
+
+#[cfg(test)]
+fn polygon(rings: Vec<Vec<[i32; 3]>>) -> Polygon {
+    use std::convert::TryInto;
+    Polygon::try_from_edges(
+        rings
+            .into_iter()
+            .map(|ring| ring.into_iter().map(|l| l.try_into().unwrap()).collect())
+            .collect(),
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+fn square(x: i32, y: i32, r: i32) -> Vec<[i32; 3]> {
+    vec![[1, 0, r - x], [0, 1, r - y], [-1, 0, r + x], [0, -1, r + y]]
+}
+
+#[test]
+fn test_valid() {
+    assert!(Polygon::default().validate().is_ok());
+    assert!(polygon(vec![square(0, 0, 1)]).validate().is_ok());
+    // A square with a hole.
+    assert!(polygon(vec![square(0, 0, 2), square(0, 0, 1)])
+        .validate()
+        .is_ok());
+    // Two squares touching at a corner.
+    assert!(polygon(vec![square(0, 0, 1), square(2, 2, 1)])
+        .validate()
+        .is_ok());
+    // A diamond touching the inside of a square's edge at its corner.
+    assert!(polygon(vec![
+        square(0, 0, 2),
+        vec![[1, 1, 0], [-1, 1, 2], [-1, -1, 2], [1, -1, 0]]
+    ])
+    .validate()
+    .is_ok());
+}
+
+#[test]
+fn test_crossing_rings() {
+    // Two overlapping squares.
+    let issues = polygon(vec![square(0, 0, 2), square(2, 2, 2)])
+        .validate()
+        .unwrap_err();
+    assert_eq!(issues.len(), 2);
+    for issue in issues {
+        match issue {
+            ValidityIssue::RingCrossing {
+                rings: [0, 1],
+                point,
+                ..
+            } => {
+                let [x, y, z]: [PointCoord; 3] = point.into();
+                assert!([x, y] == [2 * z, 0] || [x, y] == [0, 2 * z]);
+            }
+            x => panic!("{:?}", x),
+        }
+    }
+
+    // A diamond, passing straight through a square's corner.
+    let issues = polygon(vec![
+        square(0, 0, 1),
+        vec![[1, 1, 0], [-1, 1, 4], [-1, -1, 4], [1, -1, 0]],
+    ])
+    .validate()
+    .unwrap_err();
+    assert_eq!(issues.len(), 2);
+    for issue in issues {
+        match issue {
+            ValidityIssue::RingCrossing { rings: [0, 1], .. } => {}
+            x => panic!("{:?}", x),
+        }
+    }
+}
+
+#[test]
+fn test_overlaps() {
+    let issues = polygon(vec![square(0, 0, 1), square(0, 0, 1)])
+        .validate()
+        .unwrap_err();
+    assert_eq!(issues.len(), 4);
+    for issue in issues {
+        match issue {
+            ValidityIssue::DuplicateEdges {
+                rings: [0, 1],
+                edges: [e1, e2],
+            } => assert_eq!(e1, e2),
+            x => panic!("{:?}", x),
+        }
+    }
+
+    // Two squares sharing part of an edge.
+    let issues = polygon(vec![square(0, 0, 1), square(2, 1, 1)])
+        .validate()
+        .unwrap_err();
+    match issues[..] {
+        [ValidityIssue::CollinearEdges {
+            rings: [0, 1],
+            edges: [2, 0],
+        }] => {}
+        ref x => panic!("{:?}", x),
+    }
+}
+
+#[test]
+fn test_zero_area() {
+    // Three lines through the origin.
+    let issues = polygon(vec![vec![[1, 0, 0], [0, 1, 0], [1, 1, 0]]])
+        .validate()
+        .unwrap_err();
+    match issues[..] {
+        [ValidityIssue::ZeroAreaRing { ring: 0 }] => {}
+        ref x => panic!("{:?}", x),
+    }
+}
@@ -0,0 +1,208 @@
+use crate::utils::{Line, Point, PointCoord};
+use std::cmp::Ordering;
+
+/// A line segment: the part of a line between two points on it.
+///
+/// The endpoints should lie on the line, and should not be at infinity.
+/// If they don't, the predicates below give meaningless (but not undefined) answers.
+#[derive(Debug, Copy, Clone)]
+pub struct Segment {
+    /// The line the segment lies on.
+    pub line: Line,
+    /// The first endpoint.
+    pub start: Point,
+    /// The second endpoint.
+    pub end: Point,
+}
+
+/// The intersection of two segments, if it is nonempty.
+#[derive(Debug, Copy, Clone)]
+pub enum SegmentIntersection {
+    /// The segments meet at a single point.
+    Point(Point),
+    /// The segments lie on the same line, and overlap along a segment of positive length.
+    /// It has the same line and direction as the first segment.
+    Segment(Segment),
+}
+
+impl Segment {
+    /// Which side of the segment's line is the point on?
+    /// This is the same as `point.cmp_line(self.line)`.
+    pub fn side(self, point: Point) -> Ordering {
+        point.cmp_line(self.line)
+    }
+
+    /// Is the point on the segment, including its endpoints?
+    /// Points at infinity are never on a segment.
+    pub fn contains(self, point: Point) -> bool {
+        if point.cmp_line(self.line) != Ordering::Equal {
+            return false;
+        }
+
+        match (self.start.position(), point.position(), self.end.position()) {
+            (Some(a), Some(p), Some(b)) => (a <= p && p <= b) || (b <= p && p <= a),
+            _ => false,
+        }
+    }
+
+    /// Do the two segments have any point in common?
+    pub fn intersects(self, other: Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Calculate the intersection of two segments.
+    ///
+    /// ```
+    /// # use polygon3::{PointCoord, Segment, SegmentIntersection};
+    /// # use core::convert::TryInto;
+    /// // From (0, 0) to (2, 2), and from (0, 2) to (2, 0).
+    /// let s1 = Segment {
+    ///     line: [1, -1, 0].try_into().unwrap(),
+    ///     start: [0, 0, 1].try_into().unwrap(),
+    ///     end: [2, 2, 1].try_into().unwrap(),
+    /// };
+    /// let s2 = Segment {
+    ///     line: [1, 1, -2].try_into().unwrap(),
+    ///     start: [0, 2, 1].try_into().unwrap(),
+    ///     end: [2, 0, 1].try_into().unwrap(),
+    /// };
+    ///
+    /// match s1.intersection(s2) {
+    ///     Some(SegmentIntersection::Point(p)) => {
+    ///         let [x, y, z]: [PointCoord; 3] = p.into();
+    ///         assert_eq!([x, y], [z, z]);
+    ///     }
+    ///     _ => panic!(),
+    /// }
+    /// ```
+    pub fn intersection(self, other: Self) -> Option<SegmentIntersection> {
+        let point = self.line.intersect(other.line);
+        let [x, y, z]: [PointCoord; 3] = point.into();
+
+        if [x, y, z] == [0, 0, 0] {
+            // Same line. Intersect the ranges of positions.
+            let forward = self.start.position()? <= self.end.position()?;
+            let (a1, b1) = sorted(self.start, self.end)?;
+            let (a2, b2) = sorted(other.start, other.end)?;
+            let lo = if a1.position()? < a2.position()? {
+                a2
+            } else {
+                a1
+            };
+            let hi = if b1.position()? < b2.position()? {
+                b1
+            } else {
+                b2
+            };
+
+            return match lo.position()?.cmp(&hi.position()?) {
+                Ordering::Greater => None,
+                Ordering::Equal => Some(SegmentIntersection::Point(lo)),
+                Ordering::Less => {
+                    let (start, end) = if forward { (lo, hi) } else { (hi, lo) };
+                    Some(SegmentIntersection::Segment(Segment {
+                        line: self.line,
+                        start,
+                        end,
+                    }))
+                }
+            };
+        }
+
+        if z == 0 {
+            // Parallel lines.
+            return None;
+        }
+
+        let point = if z < 0 { -point } else { point };
+        if self.contains(point) && other.contains(point) {
+            Some(SegmentIntersection::Point(point))
+        } else {
+            None
+        }
+    }
+}
+
+/// Order the endpoints of a segment by position.
+fn sorted(a: Point, b: Point) -> Option<(Point, Point)> {
+    if a.position()? <= b.position()? {
+        Some((a, b))
+    } else {
+        Some((b, a))
+    }
+}
+
+#[cfg(test)]
+fn segment(line: [i32; 3], start: [i64; 3], end: [i64; 3]) -> Segment {
+    use std::convert::TryInto;
+    Segment {
+        line: line.try_into().unwrap(),
+        start: start.try_into().unwrap(),
+        end: end.try_into().unwrap(),
+    }
+}
+
+#[test]
+fn test_contains() {
+    use std::convert::TryInto;
+
+    // From (0, 0) to (4, 2).
+    let s = segment([1, -2, 0], [0, 0, 1], [8, 4, 2]);
+
+    let on = |p: [i64; 3]| s.contains(p.try_into().unwrap());
+    assert!(on([0, 0, 1]));
+    assert!(on([2, 1, 1]));
+    assert!(on([-4, -2, -1]));
+    assert!(on([4, 2, 1]));
+    assert!(!on([6, 3, 1]));
+    assert!(!on([-2, -1, 1]));
+    assert!(!on([2, 2, 1]));
+    assert!(!on([2, 1, 0]));
+}
+
+#[test]
+fn test_crossing() {
+    // The x axis from -2 to 2, and the y axis from -1 to 3.
+    let s1 = segment([0, 1, 0], [-2, 0, 1], [2, 0, 1]);
+    let s2 = segment([1, 0, 0], [0, -1, 1], [0, 3, 1]);
+    // The y axis from 1 to 3.
+    let s3 = segment([1, 0, 0], [0, 1, 1], [0, 3, 1]);
+
+    assert!(s1.intersects(s2));
+    assert!(s2.intersects(s1));
+    assert!(!s1.intersects(s3));
+    match s2.intersection(s3) {
+        Some(SegmentIntersection::Segment(s)) => {
+            assert_eq!(Into::<[PointCoord; 3]>::into(s.start), [0, 1, 1]);
+            assert_eq!(Into::<[PointCoord; 3]>::into(s.end), [0, 3, 1]);
+        }
+        x => panic!("{:?}", x),
+    }
+}
+
+#[test]
+fn test_collinear() {
+    // Pieces of the line y = x.
+    let s1 = segment([1, -1, 0], [0, 0, 1], [2, 2, 1]);
+    let s2 = segment([-2, 2, 0], [10, 10, 2], [2, 2, 1]);
+    let s3 = segment([1, -1, 0], [3, 3, 1], [4, 4, 1]);
+
+    match s1.intersection(s2) {
+        Some(SegmentIntersection::Point(p)) => {
+            assert_eq!(p.cmp_line(s1.line), Ordering::Equal);
+            let [x, y, z]: [PointCoord; 3] = p.into();
+            assert_eq!([x, y], [2 * z, 2 * z]);
+        }
+        x => panic!("{:?}", x),
+    }
+    assert!(!s1.intersects(s3));
+
+    // Overlap keeps the direction of the first segment.
+    match s2.intersection(s3) {
+        Some(SegmentIntersection::Segment(s)) => {
+            assert_eq!(Into::<[PointCoord; 3]>::into(s.start), [4, 4, 1]);
+            assert_eq!(Into::<[PointCoord; 3]>::into(s.end), [3, 3, 1]);
+        }
+        x => panic!("{:?}", x),
+    }
+}
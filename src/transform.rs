@@ -0,0 +1,568 @@
+use crate::convex_polygon::ConvexPolygon;
+use crate::polygon::Polygon;
+use crate::utils::{gcd, Line, LineCoord, OverflowError, Point, PointCoord, Wide};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+/// An invertible affine map with integer coefficients.
+///
+/// `[[a, b, c], [d, e, f]]` takes the point (x, y) to (a*x + b*y + c, d*x + e*y + f).
+///
+/// Points transform by the matrix, and lines by its adjugate transpose,
+/// so that a point is on the positive side of a line exactly when its image is on the positive side of the line's image.
+/// Results are reduced by the greatest common divisor of their coordinates,
+/// so that, for example, scaling a line by two and back gives the original line.
+///
+/// ```
+/// # use polygon3::{AffineTransform, Line, Point};
+/// # use core::convert::TryInto;
+/// let t = AffineTransform::rotation(1).then(AffineTransform::translation(3, 0)).unwrap();
+///
+/// // (1, 0) rotates to (0, 1), then moves to (3, 1).
+/// let point: Point = [1, 0, 1].try_into().unwrap();
+/// assert_eq!(t.apply(point).unwrap().to_f64_array(), [3.0, 1.0, 1.0]);
+///
+/// // The half-plane x >= 1 rotates to y >= 1, then stays put.
+/// let line: Line = [1, 0, -1].try_into().unwrap();
+/// assert_eq!(t.apply(line).unwrap().to_f64_array(), [0.0, 1.0, -1.0]);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AffineTransform([[i32; 3]; 2]);
+
+impl AffineTransform {
+    /// The map that changes nothing.
+    pub const IDENTITY: Self = Self([[1, 0, 0], [0, 1, 0]]);
+
+    /// Create a transform from its matrix. None if it is not invertible.
+    pub fn new(matrix: [[i32; 3]; 2]) -> Option<Self> {
+        let t = Self(matrix);
+        if t.determinant() == 0 {
+            None
+        } else {
+            Some(t)
+        }
+    }
+
+    /// The transform's matrix.
+    pub fn matrix(self) -> [[i32; 3]; 2] {
+        self.0
+    }
+
+    /// Move everything by (dx, dy).
+    pub fn translation(dx: i32, dy: i32) -> Self {
+        Self([[1, 0, dx], [0, 1, dy]])
+    }
+
+    /// Stretch by `sx` horizontally and `sy` vertically. A negative factor reflects.
+    /// None if either factor is zero.
+    pub fn scaling(sx: i32, sy: i32) -> Option<Self> {
+        Self::new([[sx, 0, 0], [0, sy, 0]])
+    }
+
+    /// Rotate counterclockwise about the origin, by a multiple of 90°.
+    pub fn rotation(quarter_turns: i32) -> Self {
+        match quarter_turns.rem_euclid(4) {
+            0 => Self::IDENTITY,
+            1 => Self([[0, -1, 0], [1, 0, 0]]),
+            2 => Self([[-1, 0, 0], [0, -1, 0]]),
+            _ => Self([[0, 1, 0], [-1, 0, 0]]),
+        }
+    }
+
+    /// Reflect across the x axis, taking (x, y) to (x, -y).
+    pub fn reflection_x() -> Self {
+        Self([[1, 0, 0], [0, -1, 0]])
+    }
+
+    /// Reflect across the y axis, taking (x, y) to (-x, y).
+    pub fn reflection_y() -> Self {
+        Self([[-1, 0, 0], [0, 1, 0]])
+    }
+
+    /// Take (x, y) to (x + k*y, y).
+    pub fn shear_x(k: i32) -> Self {
+        Self([[1, k, 0], [0, 1, 0]])
+    }
+
+    /// Take (x, y) to (x, y + k*x).
+    pub fn shear_y(k: i32) -> Self {
+        Self([[1, 0, 0], [k, 1, 0]])
+    }
+
+    /// Apply `self`, then `next`.
+    pub fn then(self, next: Self) -> Result<Self, OverflowError> {
+        let [[a, b, c], [d, e, f], _] = next.wide();
+        let [[g, h, i], [j, k, l], _] = self.wide();
+        let narrow = |x: Wide| match i32::try_from(x) {
+            Ok(x) if x != i32::MIN => Ok(x),
+            _ => Err(OverflowError),
+        };
+        Ok(Self([
+            [
+                narrow(a * g + b * j)?,
+                narrow(a * h + b * k)?,
+                narrow(a * i + b * l + c)?,
+            ],
+            [
+                narrow(d * g + e * j)?,
+                narrow(d * h + e * k)?,
+                narrow(d * i + e * l + f)?,
+            ],
+        ]))
+    }
+
+    /// Negative if the transform reflects.
+    pub fn determinant(self) -> i64 {
+        let [[a, b, _], [d, e, _]] = self.0;
+        i64::from(a) * i64::from(e) - i64::from(b) * i64::from(d)
+    }
+
+    /// Transform a point, line, or polygon.
+    pub fn apply<T: Transformable>(self, x: T) -> Result<T, OverflowError> {
+        x.transform(self)
+    }
+
+    fn wide(self) -> [[Wide; 3]; 3] {
+        let [r0, r1] = self.0;
+        [widen(r0), widen(r1), widen([0, 0, 1])]
+    }
+}
+
+impl Default for AffineTransform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// An invertible projective map (a homography) with integer coefficients.
+///
+/// The rows of the matrix give the homogeneous coordinates of the image: the point [x : y : z] goes to
+/// [m00*x + m01*y + m02*z : m10*x + m11*y + m12*z : m20*x + m21*y + m22*z].
+///
+/// Unlike an affine map, a projective map sends a line of the plane, the vanishing line, to infinity.
+/// A region on one side of it keeps a finite image, but a region crossing it would be torn in two,
+/// so transforming one is an error. To transform such a region, first clip it to one side.
+///
+/// ```
+/// # use polygon3::{ConvexPolygon, Line, ProjectiveTransform, TransformError};
+/// # use core::convert::TryInto;
+/// // Divide by x, sending the y axis to infinity.
+/// let t = ProjectiveTransform::new([[0, 0, 1], [0, 1, 0], [1, 0, 0]]).unwrap();
+/// let line = |l: [i32; 3]| -> Line { l.try_into().unwrap() };
+/// assert_eq!(t.vanishing_line(), Some(line([1, 0, 0])));
+///
+/// // 1 <= x <= 2, and 0 <= y <= x.
+/// let far = ConvexPolygon::from_boundaries(vec![
+///     line([1, 0, -1]),
+///     line([-1, 0, 2]),
+///     line([0, 1, 0]),
+///     line([1, -1, 0]),
+/// ])
+/// .unwrap();
+/// assert!(t.apply(far).is_ok());
+///
+/// // -1 <= x <= 1 crosses the y axis.
+/// let across = ConvexPolygon::from_boundaries(vec![line([1, 0, 1]), line([-1, 0, 1])]).unwrap();
+/// assert_eq!(t.apply(across).unwrap_err(), TransformError::CrossesInfinity);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ProjectiveTransform([[i32; 3]; 3]);
+
+impl ProjectiveTransform {
+    /// Create a transform from its matrix. None if it is not invertible,
+    /// or if an entry of the bottom row is `i32::MIN`, since that row must fit in the vanishing line's coordinates.
+    pub fn new(matrix: [[i32; 3]; 3]) -> Option<Self> {
+        if matrix[2].contains(&i32::MIN) {
+            return None;
+        }
+        let [[a, b, c], [d, e, f], [g, h, i]] = Self(matrix).wide();
+        let det = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+        if det == Wide::from(0) {
+            None
+        } else {
+            Some(Self(matrix))
+        }
+    }
+
+    /// The transform's matrix.
+    pub fn matrix(self) -> [[i32; 3]; 3] {
+        self.0
+    }
+
+    /// The line that is sent to infinity, with the side whose image is made of positive points on the positive side.
+    /// None if the transform is affine, so only the line at infinity goes to infinity.
+    pub fn vanishing_line(self) -> Option<Line> {
+        let [_, _, row] = self.0;
+        match row {
+            [0, 0, _] => None,
+            // `new` rejects i32::MIN, so the entries fit in a line's coordinates.
+            _ => Line::try_from_wide(widen(row)).ok(),
+        }
+    }
+
+    /// Transform a point, line, or polygon.
+    pub fn apply<T: ProjectiveTransformable>(self, x: T) -> Result<T, TransformError> {
+        x.transform_projective(self)
+    }
+
+    fn wide(self) -> [[Wide; 3]; 3] {
+        let [r0, r1, r2] = self.0;
+        [widen(r0), widen(r1), widen(r2)]
+    }
+}
+
+impl From<AffineTransform> for ProjectiveTransform {
+    fn from(t: AffineTransform) -> Self {
+        let [r0, r1] = t.0;
+        Self([r0, r1, [0, 0, 1]])
+    }
+}
+
+/// Why a `ProjectiveTransform` could not be applied.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TransformError {
+    /// A coordinate of the result would be out of range.
+    Overflow,
+    /// The region crosses the vanishing line, so its image would wrap around through infinity.
+    CrossesInfinity,
+}
+
+impl From<OverflowError> for TransformError {
+    fn from(_: OverflowError) -> Self {
+        Self::Overflow
+    }
+}
+
+impl std::fmt::Display for TransformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "A coordinate was out of range."),
+            Self::CrossesInfinity => write!(f, "The region crosses the line sent to infinity."),
+        }
+    }
+}
+impl std::error::Error for TransformError {}
+
+fn widen([a, b, c]: [i32; 3]) -> [Wide; 3] {
+    [a.into(), b.into(), c.into()]
+}
+
+/// Transform a point by a matrix.
+fn transform_point(m: [[Wide; 3]; 3], point: Point) -> Result<Point, OverflowError> {
+    let p: [PointCoord; 3] = point.into();
+    let p = p.map(Wide::from);
+    let row = |r: [Wide; 3]| r[0] * p[0] + r[1] * p[1] + r[2] * p[2];
+    Point::try_from_wide(reduce([row(m[0]), row(m[1]), row(m[2])]))
+}
+
+/// Transform a line by the adjugate of a matrix, which is its inverse times its determinant.
+/// A negative determinant would swap the sides of the line, so undo it.
+fn transform_line(m: [[Wide; 3]; 3], line: Line) -> Result<Line, OverflowError> {
+    let l: [LineCoord; 3] = line.into();
+    let l = l.map(Wide::from);
+    // The cofactor of entry (i, j), which is entry (j, i) of the adjugate.
+    let cofactor = |i: usize, j: usize| {
+        let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+        let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+        m[i1][j1] * m[i2][j2] - m[i1][j2] * m[i2][j1]
+    };
+    let det = (0..3).map(|j| m[0][j] * cofactor(0, j)).sum::<Wide>();
+    let sign = Wide::from(if det < Wide::from(0) { -1 } else { 1 });
+    let column = |j: usize| sign * (0..3).map(|i| l[i] * cofactor(j, i)).sum::<Wide>();
+    Line::try_from_wide(reduce([column(0), column(1), column(2)]))
+}
+
+/// Divide homogeneous coordinates by their greatest common divisor.
+fn reduce(arr: [Wide; 3]) -> [Wide; 3] {
+    let g = gcd(gcd(arr[0], arr[1]), arr[2]);
+    if g == Wide::from(0) {
+        arr
+    } else {
+        arr.map(|x| x / g)
+    }
+}
+
+/// Something an `AffineTransform` can be applied to.
+pub trait Transformable: Sized {
+    /// Apply the transform, failing if a coordinate would be out of range.
+    fn transform(self, t: AffineTransform) -> Result<Self, OverflowError>;
+}
+
+impl Transformable for Point {
+    fn transform(self, t: AffineTransform) -> Result<Self, OverflowError> {
+        transform_point(t.wide(), self)
+    }
+}
+
+impl Transformable for Line {
+    fn transform(self, t: AffineTransform) -> Result<Self, OverflowError> {
+        transform_line(t.wide(), self)
+    }
+}
+
+impl Transformable for ConvexPolygon {
+    fn transform(self, t: AffineTransform) -> Result<Self, OverflowError> {
+        let mut edges: Vec<Line> = self.into();
+        for edge in &mut edges {
+            *edge = transform_line(t.wide(), *edge)?;
+        }
+        // Keep the edges clockwise.
+        if t.determinant() < 0 {
+            edges.reverse();
+        }
+        Ok(Self::from_edges_unchecked(edges))
+    }
+}
+
+impl Transformable for Polygon {
+    fn transform(self, t: AffineTransform) -> Result<Self, OverflowError> {
+        // An invertible map keeps distinct edges distinct, and parallel edges parallel,
+        // so the result is still a valid polygon.
+        self.map_edges(|edge| transform_line(t.wide(), edge))
+    }
+}
+
+#[test]
+fn test_transforms() {
+    use std::cmp::Ordering;
+    use std::convert::TryInto;
+
+    let line = |l: [LineCoord; 3]| -> Line { l.try_into().unwrap() };
+    let transforms = [
+        AffineTransform::translation(3, -7),
+        AffineTransform::scaling(2, -3).unwrap(),
+        AffineTransform::rotation(1),
+        AffineTransform::rotation(-1),
+        AffineTransform::reflection_x(),
+        AffineTransform::reflection_y(),
+        AffineTransform::shear_x(2),
+        AffineTransform::shear_y(-1)
+            .then(AffineTransform::translation(1, 1))
+            .unwrap(),
+    ];
+
+    let triangle =
+        ConvexPolygon::from_boundaries(vec![line([1, 0, 4]), line([0, 1, 3]), line([-1, -1, 5])])
+            .unwrap();
+    let polygon = Polygon::try_from_edges(vec![
+        vec![line([1, 0, 4]), line([0, 1, 3]), line([-1, -1, 5])],
+        vec![line([1, 0, 1]), line([0, 1, 1]), line([-1, -1, 1])],
+    ])
+    .unwrap();
+
+    for &t in &transforms {
+        let convex = t.apply(triangle.clone()).unwrap();
+        convex.assert_valid();
+        let transformed = t.apply(polygon.clone()).unwrap();
+        assert!(transformed.validate().is_ok());
+
+        for x in -10..=10 {
+            for y in -10..=10 {
+                let point: Point = [2 * x + 1, 2 * y + 1, 2].try_into().unwrap();
+                let image = t.apply(point).unwrap();
+                assert_eq!(triangle.contains(point), convex.contains(image));
+                assert_eq!(polygon.contains(point), transformed.contains(image));
+            }
+        }
+    }
+
+    // Four quarter turns, and scaling there and back, change nothing.
+    let mut t = AffineTransform::IDENTITY;
+    for _ in 0..4 {
+        t = t.then(AffineTransform::rotation(1)).unwrap();
+    }
+    assert_eq!(t, AffineTransform::IDENTITY);
+    let l = line([3, 5, -7]);
+    let doubled = AffineTransform::scaling(2, 2).unwrap().apply(l).unwrap();
+    assert_eq!(doubled.to_f64_array(), [3.0, 5.0, -14.0]);
+    assert_eq!(
+        AffineTransform::new([[1, 0, 0], [0, 1, 0]]).map(|t| t.apply(l).unwrap()),
+        Some(l)
+    );
+
+    assert_eq!(AffineTransform::scaling(0, 1), None);
+    let far = AffineTransform::scaling(1 << 16, 1 << 16).unwrap();
+    assert_eq!(far.apply(line([1, 0, LineCoord::MAX])), Err(OverflowError));
+    assert_eq!(
+        AffineTransform::translation(i32::MAX, 0).then(AffineTransform::translation(1, 0)),
+        Err(OverflowError)
+    );
+    assert_eq!(
+        triangle.contains([0, 0, 1].try_into().unwrap()),
+        Ordering::Greater
+    );
+}
+
+/// Something a `ProjectiveTransform` can be applied to.
+pub trait ProjectiveTransformable: Sized {
+    /// Apply the transform, failing if a coordinate would be out of range, or if a region would cross infinity.
+    fn transform_projective(self, t: ProjectiveTransform) -> Result<Self, TransformError>;
+}
+
+/// A point beyond the vanishing line becomes negative.
+impl ProjectiveTransformable for Point {
+    fn transform_projective(self, t: ProjectiveTransform) -> Result<Self, TransformError> {
+        Ok(transform_point(t.wide(), self)?)
+    }
+}
+
+/// A point is on the positive side of a line exactly when its image is on the positive side of the line's image.
+impl ProjectiveTransformable for Line {
+    fn transform_projective(self, t: ProjectiveTransform) -> Result<Self, TransformError> {
+        Ok(transform_line(t.wide(), self)?)
+    }
+}
+
+/// The polygon must not touch the vanishing line.
+impl ProjectiveTransformable for Polygon {
+    fn transform_projective(self, t: ProjectiveTransform) -> Result<Self, TransformError> {
+        if let Some(vanishing) = t.vanishing_line() {
+            // A polygon is inside the convex hull of its vertices.
+            let sides: Vec<Ordering> = self
+                .rings()
+                .flat_map(|ring| ring.vertices())
+                .map(|vertex| vertex.cmp_line(vanishing))
+                .collect();
+            if sides.contains(&Ordering::Equal)
+                || (sides.contains(&Ordering::Less) && sides.contains(&Ordering::Greater))
+            {
+                return Err(TransformError::CrossesInfinity);
+            }
+        }
+        // The edges are unoriented, so it does not matter which side the polygon was on.
+        Ok(self.map_edges(|edge| transform_line(t.wide(), edge))?)
+    }
+}
+
+/// The region must be entirely on one side of the vanishing line.
+/// It may touch the vanishing line, in which case its image is infinite.
+/// An infinite region may have a finite image.
+impl ProjectiveTransformable for ConvexPolygon {
+    fn transform_projective(self, t: ProjectiveTransform) -> Result<Self, TransformError> {
+        let mut edges: Vec<Line> = self.into();
+
+        // Regions without interior are empty, so this asks whether any of the region is strictly on each side.
+        let beyond = match t.vanishing_line() {
+            None => false,
+            Some(vanishing) => {
+                let reaches = |l: Line| {
+                    ConvexPolygon::from_boundaries(edges.iter().copied().chain(std::iter::once(l)))
+                        .is_some()
+                };
+                match (reaches(vanishing), reaches(-vanishing)) {
+                    (true, true) => return Err(TransformError::CrossesInfinity),
+                    (reaches_front, _) => !reaches_front,
+                }
+            }
+        };
+
+        // The line at infinity bounds every region, and may have a finite image.
+        let infinity = Line::try_from_wide(widen([0, 0, 1]))?;
+        edges.push(infinity);
+        let mut edges = edges
+            .into_iter()
+            .map(|edge| transform_line(t.wide(), edge))
+            .collect::<Result<Vec<Line>, _>>()?;
+        // Beyond the vanishing line, the images are negative points, which behave oppositely.
+        if beyond {
+            edges.iter_mut().for_each(|edge| *edge = -*edge);
+        }
+        ConvexPolygon::from_boundaries(edges).ok_or(TransformError::CrossesInfinity)
+    }
+}
+
+#[test]
+fn test_projective() {
+    use std::convert::TryInto;
+
+    let line = |l: [i32; 3]| -> Line { l.try_into().unwrap() };
+    let square = |x: i32, y: i32| {
+        Polygon::try_from_edges(vec![vec![
+            line([1, 0, -x]),
+            line([0, 1, -y]),
+            line([-1, 0, x + 2]),
+            line([0, -1, y + 2]),
+        ]])
+        .unwrap()
+    };
+    // Sends x = 1 to infinity, with x < 1 in front.
+    let t = ProjectiveTransform::new([[2, 1, 0], [0, 3, 1], [-1, 0, 1]]).unwrap();
+    assert_eq!(t.vanishing_line(), Some(line([-1, 0, 1])));
+    assert_eq!(
+        ProjectiveTransform::new([[1, 2, 3], [2, 4, 6], [0, 0, 1]]),
+        None
+    );
+    // With narrow lines, this vanishing line could not be represented, and would be mistaken for none.
+    assert_eq!(
+        ProjectiveTransform::new([[1, 0, 0], [0, 1, 0], [i32::MIN, 0, 1]]),
+        None
+    );
+    let near = ProjectiveTransform::new([[1, 0, 0], [0, 1, 0], [-i32::MAX, 0, 1]]).unwrap();
+    let triangle = Polygon::from_vertices(vec![vec![[0, 0], [1, -1], [1, 1]]]).unwrap();
+    assert_eq!(
+        near.apply(triangle).unwrap_err(),
+        TransformError::CrossesInfinity
+    );
+
+    // Compare a region and its image at points off every boundary.
+    let check = |before: &dyn Fn(Point) -> Ordering, after: &dyn Fn(Point) -> Ordering| {
+        for x in -12..=12 {
+            for y in -12..=12 {
+                let point: Point = [4 * x + 1, 4 * y + 2, 4].try_into().unwrap();
+                let image = t.apply(point).unwrap();
+                let image = match image.sign() {
+                    Ordering::Less => -image,
+                    _ => image,
+                };
+                if image.sign() == Ordering::Greater {
+                    assert_eq!(before(point), after(image), "{:?}", point);
+                }
+            }
+        }
+    };
+
+    let convex = vec![
+        // In front of the vanishing line, bounded.
+        vec![line([1, 0, 3]), line([0, 1, 2]), line([-1, -1, -2])],
+        // In front, and unbounded.
+        vec![line([-1, 0, 0]), line([0, 1, 0])],
+        // Beyond, and unbounded.
+        vec![line([1, 0, -2]), line([0, 1, 1]), line([0, -1, 1])],
+        // Touching the vanishing line.
+        vec![line([-1, 0, 1]), line([1, -1, 0])],
+    ];
+    for boundaries in convex {
+        let before = ConvexPolygon::from_boundaries(boundaries).unwrap();
+        let after = t.apply(before.clone()).unwrap();
+        after.assert_valid();
+        check(&|p| before.contains(p), &|p| after.contains(p));
+    }
+    let across = ConvexPolygon::from_boundaries(vec![line([0, 1, 0])]).unwrap();
+    assert_eq!(
+        t.apply(across).unwrap_err(),
+        TransformError::CrossesInfinity
+    );
+
+    for &(x, y) in &[(-4, -1), (3, 2)] {
+        let before = square(x, y);
+        let after = t.apply(before.clone()).unwrap();
+        assert!(after.validate().is_ok());
+        check(&|p| before.contains(p), &|p| after.contains(p));
+    }
+    assert_eq!(
+        t.apply(square(0, 0)).unwrap_err(),
+        TransformError::CrossesInfinity
+    );
+
+    // Affine transforms agree.
+    let affine = AffineTransform::shear_x(3)
+        .then(AffineTransform::translation(-2, 5))
+        .unwrap();
+    let projective = ProjectiveTransform::from(affine);
+    assert_eq!(projective.vanishing_line(), None);
+    let l = line([4, -7, 9]);
+    assert_eq!(
+        projective.apply(l),
+        affine.apply(l).map_err(TransformError::from)
+    );
+}
@@ -1,15 +1,62 @@
+mod construct;
 mod conversions;
+mod rational;
+mod rounding;
+mod wide;
 
 use std::cmp::Ordering;
 
-pub use conversions::{LineMinIntError, PointMinIntError};
-
-// Throughout this module, i32s may not be 0x8000_0000, and i64s may not be 0x8000_0000_0000_0000.
+pub use construct::LineError;
+pub use conversions::{LineMinIntError, OverflowError, PointMinIntError};
+pub use rational::Rational;
+pub(crate) use rounding::power_of_two_scale;
+pub use rounding::Rounding;
+
+/// The type of a `Line`'s coefficients: `i32`, or `i64` with the `wide-coordinates` feature.
+#[cfg(not(feature = "wide-coordinates"))]
+pub type LineCoord = i32;
+/// The type of a `Point`'s coordinates: `i64`, or `i128` with the `wide-coordinates` feature.
+#[cfg(not(feature = "wide-coordinates"))]
+pub type PointCoord = i64;
+#[cfg(not(feature = "wide-coordinates"))]
+pub(crate) type Wide = i128;
+#[cfg(not(feature = "wide-coordinates"))]
+pub(crate) type Wider = wide::Int<4>;
+#[cfg(not(feature = "wide-coordinates"))]
+pub(crate) type Widest = wide::Int<8>;
+
+/// The type of a `Line`'s coefficients: `i32`, or `i64` with the `wide-coordinates` feature.
+#[cfg(feature = "wide-coordinates")]
+pub type LineCoord = i64;
+/// The type of a `Point`'s coordinates: `i64`, or `i128` with the `wide-coordinates` feature.
+#[cfg(feature = "wide-coordinates")]
+pub type PointCoord = i128;
+#[cfg(feature = "wide-coordinates")]
+pub(crate) type Wide = wide::Int<4>;
+#[cfg(feature = "wide-coordinates")]
+pub(crate) type Wider = wide::Int<8>;
+#[cfg(feature = "wide-coordinates")]
+pub(crate) type Widest = wide::Int<16>;
+
+// Throughout this module, coordinates may not be the minimum of their type, such as 0x8000_0000 for an i32.
+//
+// Write W for the number of bits in a LineCoord, which is 32 or 64.
+// Then a product of two line coefficients has at most 2W - 2 bits, and a sum of two at most 2W - 1,
+// so a point, made of such sums, fits in a PointCoord.
+// Predicates multiply a point coordinate by a line coefficient and add three, which takes 3W bits,
+// and compare products of two point coordinates, which takes 4W - 2 bits.
+// Both fit in a Wide, which has 4W bits.
+//
+// Predicates on several points, like orientation, multiply three point coordinates and add six such products.
+// That takes 6W + 2 bits, which fit in a Wider, with 8W bits.
+// The in-circle test multiplies four entries, each a sum of at most two products of two point coordinates,
+// and adds 24 such products. Each entry is less than 2^(4W - 1), so that takes less than 16W - 1 bits,
+// which fit in a Widest, with 16W bits.
 
 /// A line, with a distinguished positive and negative side.
 /// Can be the "line at infinity", in which case all (positive) points are on the same side of the line.
 #[derive(Debug, Copy, Clone)]
-pub struct Line([i32; 3]);
+pub struct Line([LineCoord; 3]);
 
 impl std::ops::Neg for Line {
     type Output = Self;
@@ -26,24 +73,25 @@ impl Line {
     pub fn intersect(self, other: Self) -> Point {
         let [x1, y1, z1] = self.0;
         let [x2, y2, z2] = other.0;
-        let x1 = i64::from(x1);
-        let y1 = i64::from(y1);
-        let z1 = i64::from(z1);
-        let x2 = i64::from(x2);
-        let y2 = i64::from(y2);
-        let z2 = i64::from(z2);
+        let x1 = PointCoord::from(x1);
+        let y1 = PointCoord::from(y1);
+        let z1 = PointCoord::from(z1);
+        let x2 = PointCoord::from(x2);
+        let y2 = PointCoord::from(y2);
+        let z2 = PointCoord::from(z2);
         Point([y1 * z2 - y2 * z1, z1 * x2 - z2 * x1, x1 * y2 - x2 * y1])
     }
 
-    pub(crate) fn slope(self) -> impl Ord {
+    /// None for the line at infinity.
+    pub(crate) fn slope(self) -> Option<impl Ord> {
         let [x, y, _] = self.0;
         match y.cmp(&0) {
-            Ordering::Greater => (1, Ratio::new(-x, y)),
-            Ordering::Less => (3, Ratio::new(-x, y)),
+            Ordering::Greater => Some((1, Ratio(-x, y))),
+            Ordering::Less => Some((3, Ratio(x, -y))),
             Ordering::Equal => match x.cmp(&0) {
-                Ordering::Greater => (0, Ratio::ZERO),
-                Ordering::Less => (2, Ratio::ZERO),
-                Ordering::Equal => panic!("Asked for slope of line at infinity."),
+                Ordering::Greater => Some((0, Ratio::ZERO)),
+                Ordering::Less => Some((2, Ratio::ZERO)),
+                Ordering::Equal => None,
             },
         }
     }
@@ -65,36 +113,29 @@ impl Line {
 
 impl PartialEq for Line {
     fn eq(&self, other: &Self) -> bool {
-        match (self.0, other.0) {
-            ([0, 0, 0], [0, 0, 0]) => true,
-            ([0, 0, 0], _) | (_, [0, 0, 0]) => false,
-            (_, _) => self.slope() == other.slope() && self.distance() == other.distance(),
+        match (self.is_infinity(), other.is_infinity()) {
+            (Some(a), Some(b)) => a == b,
+            (Some(_), None) | (None, Some(_)) => false,
+            (None, None) => self.slope() == other.slope() && self.distance() == other.distance(),
         }
     }
 }
 
 impl Eq for Line {}
 
-struct Ratio(i32, i32);
+// The denominator must be positive.
+struct Ratio(LineCoord, LineCoord);
 
 impl Ratio {
     const ZERO: Self = Self(0, 1);
-
-    fn new(a: i32, b: i32) -> Self {
-        match b.cmp(&0) {
-            Ordering::Greater => Self(a, b),
-            Ordering::Less => Self(-a, -b),
-            Ordering::Equal => panic!("Divided by zero"),
-        }
-    }
 }
 
 impl Ord for Ratio {
     fn cmp(&self, other: &Self) -> Ordering {
-        let a = i64::from(self.0);
-        let b = i64::from(self.1);
-        let c = i64::from(other.0);
-        let d = i64::from(other.1);
+        let a = PointCoord::from(self.0);
+        let b = PointCoord::from(self.1);
+        let c = PointCoord::from(other.0);
+        let d = PointCoord::from(other.1);
         (a * d).cmp(&(b * c))
     }
 }
@@ -116,7 +157,7 @@ impl PartialEq for Ratio {
 #[derive(Debug, Copy, Clone)]
 /// A signed point. Negative points behave oppositely when tested against lines.
 /// Can also be point at infinity. Opposite points at infinity are not identified.
-pub struct Point([i64; 3]);
+pub struct Point([PointCoord; 3]);
 
 impl Point {
     /// Test a point against a line.
@@ -138,13 +179,56 @@ impl Point {
     pub fn cmp_line(self, l: Line) -> Ordering {
         let [x1, y1, z1] = self.0;
         let [x2, y2, z2] = l.0;
-        let x1 = i128::from(x1);
-        let y1 = i128::from(y1);
-        let z1 = i128::from(z1);
-        let x2 = i128::from(x2);
-        let y2 = i128::from(y2);
-        let z2 = i128::from(z2);
-        (x1 * x2 + y1 * y2 + z1 * z2).cmp(&0)
+        let x1 = Wide::from(x1);
+        let y1 = Wide::from(y1);
+        let z1 = Wide::from(z1);
+        let x2 = Wide::from(x2);
+        let y2 = Wide::from(y2);
+        let z2 = Wide::from(z2);
+        (x1 * x2 + y1 * y2 + z1 * z2).cmp(&Wide::from(0))
+    }
+}
+
+/// Projective equality: two points are equal if one is a positive multiple of the other.
+/// Opposite points are not equal.
+impl PartialEq for Point {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Point {}
+
+/// Finite points are ordered lexicographically by (y, x), the order in which the sweep line reaches them.
+/// A negative point comes just after the positive point at the same position.
+/// Points at infinity come after every finite point, by counterclockwise angle from the positive x axis,
+/// and the degenerate point [0 : 0 : 0] comes last.
+/// ```
+/// # use polygon3::Point;
+/// # use core::convert::TryInto;
+/// let point = |p: [i64; 3]| -> Point { p.try_into().unwrap() };
+///
+/// assert_eq!(point([1, 2, 1]), point([2, 4, 2]));
+/// assert_ne!(point([1, 2, 1]), point([-1, -2, -1]));
+/// assert!(point([5, 0, 1]) < point([0, 1, 1]));
+/// assert!(point([0, 1, 1]) < point([1, 1, 1]));
+/// assert!(point([1, 1, 1]) < point([-1, -1, -1]));
+/// assert!(point([9, 9, 1]) < point([1, 0, 0]));
+/// ```
+impl Ord for Point {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.position(), other.position()) {
+            (Some(a), Some(b)) => a.cmp(&b).then_with(|| other.sign().cmp(&self.sign())),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => self.direction().cmp(&other.direction()),
+        }
+    }
+}
+
+impl PartialOrd for Point {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -165,46 +249,152 @@ impl Point {
         self.0[2].cmp(&0)
     }
 
-    pub(crate) fn x_coord(self) -> impl Ord {
-        struct Ratio(i64, i64);
+    /// Which way three points turn. Exact over the full range of coordinates.
+    ///
+    /// For positive finite points, Ordering::Greater means counterclockwise, and Ordering::Equal means collinear.
+    /// Each negative point reverses the result.
+    /// ```
+    /// # use polygon3::Point;
+    /// # use core::convert::TryInto;
+    /// use std::cmp::Ordering;
+    ///
+    /// let p: Point = [0, 0, 1].try_into().unwrap();
+    /// let q: Point = [1, 0, 1].try_into().unwrap();
+    /// let r: Point = [0, 1, 1].try_into().unwrap();
+    ///
+    /// assert_eq!(Point::orient(p, q, r), Ordering::Greater);
+    /// assert_eq!(Point::orient(p, r, q), Ordering::Less);
+    /// assert_eq!(Point::orient(p, q, -r), Ordering::Less);
+    /// ```
+    pub fn orient(p: Self, q: Self, r: Self) -> Ordering {
+        let [p, q, r] = [p.0, q.0, r.0].map(|c| c.map(Wider::from));
+        let minor = |i: usize, j: usize| q[i] * r[j] - q[j] * r[i];
+        (p[0] * minor(1, 2) - p[1] * minor(0, 2) + p[2] * minor(0, 1)).cmp(&Wider::from(0))
+    }
 
-        impl Ratio {
-            fn new(a: i64, b: i64) -> Self {
-                match b.cmp(&0) {
-                    Ordering::Greater => Self(a, b),
-                    Ordering::Less => Self(-a, -b),
-                    Ordering::Equal => panic!("Divided by zero"),
-                }
-            }
-        }
+    /// Is `d` inside the circle through `a`, `b`, and `c`? Exact over the full range of coordinates.
+    ///
+    /// If `a`, `b`, and `c` turn counterclockwise, Ordering::Greater means inside, and Ordering::Equal means on the circle.
+    /// If they turn clockwise, the result is reversed.
+    /// The signs of the points do not matter, but they must be finite.
+    /// ```
+    /// # use polygon3::Point;
+    /// # use core::convert::TryInto;
+    /// use std::cmp::Ordering;
+    ///
+    /// let point = |p: [i64; 3]| -> Point { p.try_into().unwrap() };
+    /// let (a, b, c) = (point([1, 0, 1]), point([0, 1, 1]), point([-1, 0, 1]));
+    ///
+    /// assert_eq!(Point::in_circle(a, b, c, point([0, 0, 1])), Ordering::Greater);
+    /// assert_eq!(Point::in_circle(a, b, c, point([0, -1, 1])), Ordering::Equal);
+    /// assert_eq!(Point::in_circle(a, b, c, point([2, 0, 1])), Ordering::Less);
+    /// assert_eq!(Point::in_circle(a, c, b, point([0, 0, 1])), Ordering::Less);
+    /// ```
+    pub fn in_circle(a: Self, b: Self, c: Self, d: Self) -> Ordering {
+        // Each row is (x, y, x² + y², 1), scaled by z², which is positive.
+        let rows = [a.0, b.0, c.0, d.0].map(|p| {
+            let [x, y, z] = p.map(Widest::from);
+            [x * z, y * z, x * x + y * y, z * z]
+        });
+        // Expand along the first two rows, by complementary pairs of columns.
+        let minor = |r: usize, i: usize, j: usize| {
+            rows[r][i] * rows[r + 1][j] - rows[r][j] * rows[r + 1][i]
+        };
+        let det = minor(0, 0, 1) * minor(2, 2, 3) - minor(0, 0, 2) * minor(2, 1, 3)
+            + minor(0, 0, 3) * minor(2, 1, 2)
+            + minor(0, 1, 2) * minor(2, 0, 3)
+            - minor(0, 1, 3) * minor(2, 0, 2)
+            + minor(0, 2, 3) * minor(2, 0, 1);
+        det.cmp(&Widest::from(0))
+    }
 
-        impl Ord for Ratio {
-            fn cmp(&self, other: &Self) -> Ordering {
-                let a = i128::from(self.0);
-                let b = i128::from(self.1);
-                let c = i128::from(other.0);
-                let d = i128::from(other.1);
-                (a * d).cmp(&(b * c))
-            }
-        }
+    /// None if the point is at infinity.
+    pub(crate) fn x_coord(self) -> Option<impl Ord> {
+        let [x, _, z] = self.0;
+        CoordRatio::new(x, z)
+    }
 
-        impl PartialOrd for Ratio {
-            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-                Some(self.cmp(other))
-            }
-        }
+    /// None if the point is at infinity.
+    pub(crate) fn y_coord(self) -> Option<impl Ord> {
+        let [_, y, z] = self.0;
+        CoordRatio::new(y, z)
+    }
 
-        impl Eq for Ratio {}
+    /// For a point at infinity, its counterclockwise angle from the positive x axis.
+    /// The degenerate point [0 : 0 : 0] comes after every angle.
+    fn direction(self) -> impl Ord {
+        let [x, y, _] = self.0;
+        let half = !(y > 0 || (y == 0 && x > 0));
+        (x == 0 && y == 0, half, Angle(x, y))
+    }
+
+    /// Position along the sweep direction: lexicographic by (y, x).
+    /// Along any line, this orders points monotonically.
+    /// None if the point is at infinity.
+    pub(crate) fn position(self) -> Option<impl Ord> {
+        Some((self.y_coord()?, self.x_coord()?))
+    }
+}
 
-        impl PartialEq for Ratio {
-            fn eq(&self, other: &Self) -> bool {
-                self.cmp(other) == Ordering::Equal
-            }
+struct CoordRatio(PointCoord, PointCoord);
+
+impl CoordRatio {
+    /// None if dividing by zero.
+    fn new(a: PointCoord, b: PointCoord) -> Option<Self> {
+        match b.cmp(&0) {
+            Ordering::Greater => Some(Self(a, b)),
+            Ordering::Less => Some(Self(-a, -b)),
+            Ordering::Equal => None,
         }
+    }
+}
 
-        let [x, _, z] = self.0;
+impl Ord for CoordRatio {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let a = Wide::from(self.0);
+        let b = Wide::from(self.1);
+        let c = Wide::from(other.0);
+        let d = Wide::from(other.1);
+        (a * d).cmp(&(b * c))
+    }
+}
+
+impl PartialOrd for CoordRatio {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Eq for CoordRatio {}
+
+impl PartialEq for CoordRatio {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+// A direction, compared within a half-plane of directions, so that it is less than anything counterclockwise of it.
+struct Angle(PointCoord, PointCoord);
+
+impl Ord for Angle {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let cross =
+            Wide::from(self.0) * Wide::from(other.1) - Wide::from(self.1) * Wide::from(other.0);
+        Wide::from(0).cmp(&cross)
+    }
+}
 
-        Ratio::new(x, z)
+impl PartialOrd for Angle {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Eq for Angle {}
+
+impl PartialEq for Angle {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
     }
 }
 
@@ -224,10 +414,10 @@ impl UnorientedLine {
     pub fn angle_from_horizontal(self) -> impl Ord {
         let [x, y, _] = (self.0).0;
 
-        if x == 0 {
-            (0, Ratio::ZERO)
-        } else {
-            (1, Ratio::new(y, x))
+        match x.cmp(&0) {
+            Ordering::Equal => (0, Ratio::ZERO),
+            Ordering::Greater => (1, Ratio(y, x)),
+            Ordering::Less => (1, Ratio(-y, -x)),
         }
     }
 }
@@ -238,6 +428,105 @@ impl PartialEq for UnorientedLine {
     }
 }
 
+/// The greatest common divisor, which is never negative.
+pub(crate) fn gcd<T>(a: T, b: T) -> T
+where
+    T: Copy + Ord + From<i32> + std::ops::Neg<Output = T> + std::ops::Rem<Output = T>,
+{
+    let zero = T::from(0);
+    let abs = |x: T| if x < zero { -x } else { x };
+    let (mut a, mut b) = (abs(a), abs(b));
+    while b != zero {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
 pub fn pairs<T>(slice: &[T]) -> impl Iterator<Item = (&T, &T)> {
     slice.iter().zip(slice.iter().cycle().skip(1))
 }
+
+#[test]
+fn test_lines_at_infinity() {
+    use std::convert::TryInto;
+
+    let line = |l: [i32; 3]| -> Line { l.try_into().unwrap() };
+    assert_eq!(line([0, 0, 1]), line([0, 0, 3]));
+    assert_ne!(line([0, 0, 1]), line([0, 0, -1]));
+    assert_ne!(line([0, 0, 1]), line([0, 1, 1]));
+    assert_ne!(line([1, 0, 0]), line([0, 0, 1]));
+    assert_eq!(line([1, -1, 2]), line([2, -2, 4]));
+}
+
+#[test]
+fn test_orient() {
+    use std::convert::TryInto;
+
+    let m = PointCoord::MAX;
+    let point = |p: [PointCoord; 3]| -> Point { p.try_into().unwrap() };
+    let (p, q) = (point([m, 0, 1]), point([0, m, 1]));
+    // The products here are far past the range of a Wide.
+    assert_eq!(Point::orient(p, q, point([m - 1, 1, 1])), Ordering::Equal);
+    assert_eq!(Point::orient(p, q, point([m - 1, 2, 1])), Ordering::Less);
+    assert_eq!(Point::orient(p, q, point([m - 1, 0, 1])), Ordering::Greater);
+    assert_eq!(Point::orient(p, q, point([1 - m, 0, -1])), Ordering::Less);
+    assert_eq!(Point::orient(p, q, point([-1, 1, 0])), Ordering::Equal);
+}
+
+#[test]
+fn test_point_order() {
+    use std::collections::BTreeSet;
+    use std::convert::TryInto;
+
+    let m = PointCoord::MAX;
+    let point = |p: [PointCoord; 3]| -> Point { p.try_into().unwrap() };
+
+    // Exact near the limits of the coordinates.
+    assert_eq!(point([m - 1, m - 1, m]), point([m - 1, m - 1, m]));
+    assert!(point([m - 1, m - 1, m]) < point([m - 2, m - 1, m - 1]));
+    assert_ne!(point([1, 1, m]), point([1, 1, m - 1]));
+
+    let points: BTreeSet<Point> = vec![
+        point([2, 4, 2]),
+        point([1, 2, 1]),
+        point([-1, -2, -1]),
+        point([0, 0, 0]),
+        point([-3, 0, 0]),
+        point([1, 0, 0]),
+        point([2, 0, 0]),
+        point([0, 1, 1]),
+    ]
+    .into_iter()
+    .collect();
+    let sorted: Vec<Point> = points.into_iter().collect();
+    assert_eq!(
+        sorted,
+        vec![
+            point([0, 1, 1]),
+            point([1, 2, 1]),
+            point([-1, -2, -1]),
+            point([1, 0, 0]),
+            point([-3, 0, 0]),
+            point([0, 0, 0]),
+        ]
+    );
+
+    // Four points on a circle of radius m - 1, centred at the origin.
+    let (a, b, c) = (
+        point([m - 1, 0, 1]),
+        point([0, m - 1, 1]),
+        point([1 - m, 0, 1]),
+    );
+    assert_eq!(
+        Point::in_circle(a, b, c, point([0, 1 - m, 1])),
+        Ordering::Equal
+    );
+    assert_eq!(
+        Point::in_circle(a, b, c, point([0, 2 - m, 1])),
+        Ordering::Greater
+    );
+    assert_eq!(Point::in_circle(a, b, c, point([0, m, 1])), Ordering::Less);
+    assert_eq!(Point::in_circle(a, b, -c, point([0, m, 1])), Ordering::Less);
+}
@@ -0,0 +1,183 @@
+use super::{gcd, Line, LineCoord, OverflowError, Point, Widest};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+/// Why a line could not be constructed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineError {
+    /// A coefficient would not fit in a `LineCoord`, or would be its minimum.
+    Overflow,
+    /// The inputs do not determine a line, such as two equal points.
+    Degenerate,
+}
+
+impl From<OverflowError> for LineError {
+    fn from(_: OverflowError) -> Self {
+        Self::Overflow
+    }
+}
+
+impl std::fmt::Display for LineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "A coefficient of the line was out of range."),
+            Self::Degenerate => write!(f, "The inputs do not determine a line."),
+        }
+    }
+}
+impl std::error::Error for LineError {}
+
+impl Line {
+    /// The line through two points, with the positive side to the left of the direction from `p` to `q`.
+    ///
+    /// Either point may be at infinity, in which case the line goes in its direction.
+    /// Fails if the points are equal or opposite.
+    /// ```
+    /// # use polygon3::{Line, Point};
+    /// # use core::convert::TryInto;
+    /// let p: Point = [0, 0, 1].try_into().unwrap();
+    /// let q: Point = [2, 2, 1].try_into().unwrap();
+    /// let line = Line::through(p, q).unwrap();
+    ///
+    /// assert_eq!(line.to_f64_array(), [-1.0, 1.0, 0.0]);
+    /// assert!(Line::through(p, p).is_err());
+    /// ```
+    pub fn through(p: Point, q: Point) -> Result<Self, LineError> {
+        let [p, q] = [p, q].map(|point| widen(positive(point)));
+        reduce([
+            p[1] * q[2] - p[2] * q[1],
+            p[2] * q[0] - p[0] * q[2],
+            p[0] * q[1] - p[1] * q[0],
+        ])
+    }
+
+    /// The line through a finite point, parallel to `self`, with its positive side facing the same way.
+    pub fn parallel_through(self, p: Point) -> Result<Self, LineError> {
+        let [a, b, _] = self.0.map(Widest::from);
+        let [x, y, z] = widen(finite(p)?);
+        reduce([a * z, b * z, -(a * x + b * y)])
+    }
+
+    /// The line through a finite point, perpendicular to `self`.
+    /// Its positive side is ahead, when moving along `self` with the positive side of `self` on the left.
+    pub fn perpendicular_through(self, p: Point) -> Result<Self, LineError> {
+        let [a, b, _] = self.0.map(Widest::from);
+        let [x, y, z] = widen(finite(p)?);
+        reduce([b * z, -a * z, a * y - b * x])
+    }
+
+    /// The perpendicular bisector of two finite points, with `q` on the positive side.
+    /// ```
+    /// # use polygon3::{Line, Point};
+    /// # use core::convert::TryInto;
+    /// let p: Point = [0, 0, 1].try_into().unwrap();
+    /// let q: Point = [2, 0, 1].try_into().unwrap();
+    /// let line = Line::bisector(p, q).unwrap();
+    ///
+    /// assert_eq!(line.to_f64_array(), [1.0, 0.0, -1.0]);
+    /// assert_eq!(q.cmp_line(line), std::cmp::Ordering::Greater);
+    /// ```
+    pub fn bisector(p: Point, q: Point) -> Result<Self, LineError> {
+        let [px, py, pz] = widen(finite(p)?);
+        let [qx, qy, qz] = widen(finite(q)?);
+        // Points (x, y) with |(x, y) - p|² - |(x, y) - q|² = 0, scaled by 2 pz² qz².
+        let two = Widest::from(2);
+        reduce([
+            two * pz * qz * (qx * pz - px * qz),
+            two * pz * qz * (qy * pz - py * qz),
+            (px * px + py * py) * qz * qz - (qx * qx + qy * qy) * pz * pz,
+        ])
+    }
+}
+
+/// The positive point at the same place.
+fn positive(point: Point) -> Point {
+    if point.sign() == Ordering::Less {
+        -point
+    } else {
+        point
+    }
+}
+
+/// The positive point at the same place, which must be finite.
+fn finite(point: Point) -> Result<Point, LineError> {
+    match point.sign() {
+        Ordering::Equal => Err(LineError::Degenerate),
+        _ => Ok(positive(point)),
+    }
+}
+
+fn widen(point: Point) -> [Widest; 3] {
+    point.0.map(Widest::from)
+}
+
+/// Divide out common factors, and narrow to a line.
+fn reduce(arr: [Widest; 3]) -> Result<Line, LineError> {
+    let g = gcd(gcd(arr[0], arr[1]), arr[2]);
+    if g == Widest::from(0) {
+        return Err(LineError::Degenerate);
+    }
+    let narrow = |x: Widest| match LineCoord::try_from(x / g) {
+        Ok(x) if x != LineCoord::MIN => Ok(x),
+        _ => Err(LineError::Overflow),
+    };
+    Ok(Line([narrow(arr[0])?, narrow(arr[1])?, narrow(arr[2])?]))
+}
+
+#[test]
+fn test_construct() {
+    use super::PointCoord;
+    use std::convert::TryInto;
+
+    let point = |p: [PointCoord; 3]| -> Point { p.try_into().unwrap() };
+    let line = |l: [i32; 3]| -> Line { l.try_into().unwrap() };
+    let (p, q) = (point([1, 2, 1]), point([3, 5, 1]));
+
+    // Homogeneous scale and sign do not matter.
+    let l = Line::through(p, q).unwrap();
+    assert_eq!(Line::through(point([-2, -4, -2]), q), Ok(l));
+    assert_eq!(Line::through(q, p), Ok(-l));
+    assert_eq!(point([0, 10, 1]).cmp_line(l), Ordering::Greater);
+    assert_eq!(point([5, 8, 1]).cmp_line(l), Ordering::Equal);
+    assert_eq!(Line::through(p, point([2, 3, 0])), Ok(l));
+    assert_eq!(
+        Line::through(p, point([-1, -2, -1])),
+        Err(LineError::Degenerate)
+    );
+
+    let horizontal = line([0, 1, -4]);
+    assert_eq!(horizontal.parallel_through(p), Ok(line([0, 1, -2])));
+    assert_eq!(line([0, -3, 7]).parallel_through(p), Ok(line([0, -1, 2])));
+    assert_eq!(horizontal.perpendicular_through(p), Ok(line([1, 0, -1])));
+    assert_eq!(
+        line([0, -1, 4]).perpendicular_through(p),
+        Ok(line([-1, 0, 1]))
+    );
+    assert_eq!(
+        horizontal.parallel_through(point([1, 0, 0])),
+        Err(LineError::Degenerate)
+    );
+    assert_eq!(
+        line([0, 0, 1]).parallel_through(p),
+        Err(LineError::Degenerate)
+    );
+
+    let b = Line::bisector(p, point([3, 2, 1])).unwrap();
+    assert_eq!(b, line([1, 0, -2]));
+    assert_eq!(
+        Line::bisector(p, point([2, 4, 2])),
+        Err(LineError::Degenerate)
+    );
+    // Halves: (1/2, 1) and (3/2, 1) are bisected by x = 1.
+    assert_eq!(
+        Line::bisector(point([1, 2, 2]), point([-3, -2, -2])),
+        Ok(line([1, 0, -1]))
+    );
+
+    let m = PointCoord::from(LineCoord::MAX);
+    let far = point([m * 4, 0, 1]);
+    assert_eq!(
+        Line::bisector(point([0, 0, 1]), far),
+        Err(LineError::Overflow)
+    );
+}
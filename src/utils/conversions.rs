@@ -1,11 +1,13 @@
-use super::{Line, Point};
+use super::rounding::power_of_two_scale;
+use super::{Line, LineCoord, Point, PointCoord, Wide};
+use std::convert::TryFrom;
 
 
-impl std::convert::TryFrom<[i32; 3]> for Line {
+impl std::convert::TryFrom<[LineCoord; 3]> for Line {
 	type Error = LineMinIntError;
 	/// Given [a,b,c], return the line (a*x+b*y+c).cmp(0)
-	fn try_from(arr: [i32; 3]) -> Result<Self, LineMinIntError> {
-		if arr[0] == std::i32::MIN || arr[1] == std::i32::MIN || arr[2] == std::i32::MIN {
+	fn try_from(arr: [LineCoord; 3]) -> Result<Self, LineMinIntError> {
+		if arr[0] == LineCoord::MIN || arr[1] == LineCoord::MIN || arr[2] == LineCoord::MIN {
 			return Err(LineMinIntError);
 		}
 		Ok(Self(arr))
@@ -13,12 +15,12 @@ impl std::convert::TryFrom<[i32; 3]> for Line {
 }
 
 #[derive(Debug)]
-/// This error occurs if you try to make a line with a, b, or c equal to -2_147_483_648 (0x8000_0000), because that number breaks operations like negation.
+/// This error occurs if you try to make a line with a, b, or c equal to the minimum `LineCoord`, such as -2_147_483_648 (0x8000_0000), because that number breaks operations like negation.
 pub struct LineMinIntError;
 
 impl std::fmt::Display for LineMinIntError {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-		write!(f, "Tried to create a line with coordinate -2_147_483_648 (0x8000_0000). This is not allowed, because it breaks operations like negation.")
+		write!(f, "Tried to create a line with coordinate {}. This is not allowed, because it breaks operations like negation.", LineCoord::MIN)
 	}
 }
 impl std::error::Error for LineMinIntError {}
@@ -26,19 +28,28 @@ impl std::error::Error for LineMinIntError {}
 
 
 
-impl Into<[i32; 3]> for Line {
+/// With wide coordinates, narrow lines still convert, so code written for them keeps working.
+#[cfg(feature = "wide-coordinates")]
+impl From<[i32; 3]> for Line {
+	fn from([a, b, c]: [i32; 3]) -> Self {
+		// i32::MIN widens to a value other than i64::MIN.
+		Self([a.into(), b.into(), c.into()])
+	}
+}
+
+impl Into<[LineCoord; 3]> for Line {
 	/// Given the line (a*x+b*y+c).cmp(0), return [a,b,c].
-	fn into(self) -> [i32; 3] {
+	fn into(self) -> [LineCoord; 3] {
 		self.0
 	}
 }
 
 
-impl std::convert::TryFrom<[i64; 3]> for Point {
+impl std::convert::TryFrom<[PointCoord; 3]> for Point {
 	type Error = PointMinIntError;
 	/// Create a point from homogeneous coordinates. The point will have the same sign as the z coordinate.
-	fn try_from(arr: [i64; 3]) -> Result<Self, PointMinIntError> {
-		if arr[0] == std::i64::MIN || arr[1] == std::i64::MIN || arr[2] == std::i64::MIN {
+	fn try_from(arr: [PointCoord; 3]) -> Result<Self, PointMinIntError> {
+		if arr[0] == PointCoord::MIN || arr[1] == PointCoord::MIN || arr[2] == PointCoord::MIN {
 			return Err(PointMinIntError);
 		}
 		Ok(Self(arr))
@@ -46,21 +57,37 @@ impl std::convert::TryFrom<[i64; 3]> for Point {
 }
 
 #[derive(Debug)]
-/// This error occurs if you try to make a point with x, y, or z equal to -9_223_372_036_854_775_808 (0x8000_0000_0000_0000), because that number breaks operations like negation.
+/// This error occurs if you try to make a point with x, y, or z equal to the minimum `PointCoord`, such as -9_223_372_036_854_775_808 (0x8000_0000_0000_0000), because that number breaks operations like negation.
 pub struct PointMinIntError;
 
 impl std::fmt::Display for PointMinIntError {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-		write!(f, "Tried to create a point with coordinate -9_223_372_036_854_775_808 (0x8000_0000_0000_0000). This is not allowed, because it breaks operations like negation.")
+		write!(f, "Tried to create a point with coordinate {}. This is not allowed, because it breaks operations like negation.", PointCoord::MIN)
 	}
 }
 impl std::error::Error for PointMinIntError {}
 
 
 
-impl Into<[i64; 3]> for Point {
+/// With wide coordinates, narrow points still convert, so code written for them keeps working.
+#[cfg(feature = "wide-coordinates")]
+impl From<[i64; 3]> for Point {
+	fn from([x, y, z]: [i64; 3]) -> Self {
+		// i64::MIN widens to a value other than i128::MIN.
+		Self([x.into(), y.into(), z.into()])
+	}
+}
+
+#[cfg(feature = "wide-coordinates")]
+impl From<[i32; 3]> for Point {
+	fn from([x, y, z]: [i32; 3]) -> Self {
+		Self([x.into(), y.into(), z.into()])
+	}
+}
+
+impl Into<[PointCoord; 3]> for Point {
 	/// Return a point's homogeneous coordinates. The z coordinate will have the same sign as the point.
-	fn into(self) -> [i64; 3] {
+	fn into(self) -> [PointCoord; 3] {
 		self.0
 	}
 }
@@ -72,30 +99,51 @@ impl Into<[i64; 3]> for Point {
 
 
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// This error occurs if a calculated line or point would have a coordinate that does not fit, or that equals the minimum integer.
+pub struct OverflowError;
+
+impl std::fmt::Display for OverflowError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "A coordinate was out of range.")
+	}
+}
+impl std::error::Error for OverflowError {}
+
 impl Line {
-	/// Convert from homogeneous coordinates. Inexact, because of floating point input.
-	pub fn try_from_f64_array([mut a, mut b, mut c]: [f64; 3]) -> Option<Self> {
-		let m = a.abs().max(b.abs()).max(c.abs());
-
-		a /= m;
-		b /= m;
-		c /= m;
-
-		a *= f64::from(std::i32::MAX);
-		b *= f64::from(std::i32::MAX);
-		c *= f64::from(std::i32::MAX);
-
-		if a.is_finite() && b.is_finite() && c.is_finite() {
-			Some(Self([a as i32, b as i32, c as i32]))
-		} else {
-			None
-		}
+	/// Narrow wide homogeneous coordinates, with the same checks as `Line::try_from`.
+	pub(crate) fn try_from_wide(arr: [Wide; 3]) -> Result<Self, OverflowError> {
+		let narrow = |x: Wide| match LineCoord::try_from(x) {
+			Ok(x) if x != LineCoord::MIN => Ok(x),
+			_ => Err(OverflowError),
+		};
+		Ok(Self([narrow(arr[0])?, narrow(arr[1])?, narrow(arr[2])?]))
+	}
+}
+
+impl Point {
+	/// Narrow wide homogeneous coordinates, with the same checks as `Point::try_from`.
+	pub(crate) fn try_from_wide(arr: [Wide; 3]) -> Result<Self, OverflowError> {
+		let narrow = |x: Wide| match PointCoord::try_from(x) {
+			Ok(x) if x != PointCoord::MIN => Ok(x),
+			_ => Err(OverflowError),
+		};
+		Ok(Self([narrow(arr[0])?, narrow(arr[1])?, narrow(arr[2])?]))
 	}
+}
 
-	/// Convert to homogeneous coordinates. Exact, despite conversion to floating point.
+impl Line {
+	/// Convert from homogeneous coordinates, rounding each to the nearest integer after scaling by a power of two.
+	/// Inexact, because of floating point input; see `Line::try_from_f64_rounded` for a bound on the error.
+	pub fn try_from_f64_array(arr: [f64; 3]) -> Option<Self> {
+		let [a, b, c] = round_scaled(arr, MAX_F64_THAT_FITS_IN_LINE_COORD)?;
+		Some(Self([a as LineCoord, b as LineCoord, c as LineCoord]))
+	}
+
+	/// Convert to homogeneous coordinates. Exact for `i32` coefficients, despite conversion to floating point.
 	pub fn to_f64_array(self) -> [f64; 3] {
 		let [a, b, c] = self.0;
-		[f64::from(a), f64::from(b), f64::from(c)]
+		[a as f64, b as f64, c as f64]
 	}
 }
 
@@ -104,29 +152,49 @@ impl Line {
 /// ```
 const MAX_F64_THAT_FITS_IN_I64: f64 = 0x7FFF_FFFF_FFFF_FC00_i64 as f64;
 
+/// ```
+/// assert_eq!((0x7FFF_FFFF_FFFF_FC00_0000_0000_0000_0000_i128 as f64) as i128, 0x7FFF_FFFF_FFFF_FC00_0000_0000_0000_0000);
+/// ```
+#[cfg(feature = "wide-coordinates")]
+const MAX_F64_THAT_FITS_IN_I128: f64 = 0x7FFF_FFFF_FFFF_FC00_0000_0000_0000_0000_i128 as f64;
+
+#[cfg(not(feature = "wide-coordinates"))]
+pub(super) const MAX_F64_THAT_FITS_IN_LINE_COORD: f64 = i32::MAX as f64;
+#[cfg(not(feature = "wide-coordinates"))]
+pub(super) const MAX_F64_THAT_FITS_IN_POINT_COORD: f64 = MAX_F64_THAT_FITS_IN_I64;
+
+#[cfg(feature = "wide-coordinates")]
+pub(super) const MAX_F64_THAT_FITS_IN_LINE_COORD: f64 = MAX_F64_THAT_FITS_IN_I64;
+#[cfg(feature = "wide-coordinates")]
+pub(super) const MAX_F64_THAT_FITS_IN_POINT_COORD: f64 = MAX_F64_THAT_FITS_IN_I128;
+
 impl Point {
-	/// Convert from homogeneous coordinates. Inexact, because of floating point input.
-	pub fn try_from_f64_array([mut a, mut b, mut c]: [f64; 3]) -> Option<Self> {
-		let m = a.abs().max(b.abs()).max(c.abs());
-
-		a /= m;
-		b /= m;
-		c /= m;
-
-		a *= MAX_F64_THAT_FITS_IN_I64;
-		b *= MAX_F64_THAT_FITS_IN_I64;
-		c *= MAX_F64_THAT_FITS_IN_I64;
-
-		if a.is_finite() && b.is_finite() && c.is_finite() {
-			Some(Self([a as i64, b as i64, c as i64]))
-		} else {
-			None
-		}
+	/// Convert from homogeneous coordinates, rounding each to the nearest integer after scaling by a power of two.
+	/// Inexact, because of floating point input; see `Point::try_from_f64_rounded` for a bound on the error.
+	pub fn try_from_f64_array(arr: [f64; 3]) -> Option<Self> {
+		let [a, b, c] = round_scaled(arr, MAX_F64_THAT_FITS_IN_POINT_COORD)?;
+		Some(Self([a as PointCoord, b as PointCoord, c as PointCoord]))
 	}
 
 	/// Convert to homogeneous coordinates. Inexact, because of loss of precision in conversion to floating point.
+	/// For Cartesian coordinates, `to_f64_rounded` avoids rounding twice.
 	pub fn to_f64_array(self) -> [f64; 3] {
 		let [a, b, c] = self.0;
 		[a as f64, b as f64, c as f64]
 	}
 }
+
+/// Scale finite coordinates by a power of two, so the largest is at most `limit`, and round them.
+/// None if they are not finite, or all zero.
+fn round_scaled(arr: [f64; 3], limit: f64) -> Option<[f64; 3]> {
+	if !arr.iter().all(|x| x.is_finite()) {
+		return None;
+	}
+	let max = arr.iter().fold(0.0, |m: f64, x| m.max(x.abs()));
+	if max == 0.0 {
+		return None;
+	}
+	// `limit` is an integer, so rounding cannot exceed it.
+	let s = power_of_two_scale(max, limit);
+	Some(arr.map(|x| (x * s).round()))
+}
@@ -0,0 +1,220 @@
+use super::{gcd, Point, PointCoord, Wide};
+use std::cmp::Ordering;
+
+/// An exact fraction, in lowest terms, with a positive denominator.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: PointCoord,
+    denom: PointCoord,
+}
+
+impl Rational {
+    /// Reduce `numer / denom`. None if the denominator is zero.
+    /// Neither may be the minimum `PointCoord`.
+    fn new(numer: PointCoord, denom: PointCoord) -> Option<Self> {
+        if denom == 0 {
+            return None;
+        }
+        let g = gcd(numer, denom);
+        let sign = if denom < 0 { -1 } else { 1 };
+        Some(Self {
+            numer: sign * (numer / g),
+            denom: sign * (denom / g),
+        })
+    }
+
+    pub fn numer(self) -> PointCoord {
+        self.numer
+    }
+
+    /// Always positive.
+    pub fn denom(self) -> PointCoord {
+        self.denom
+    }
+
+    /// The greatest integer not above `self`.
+    pub fn floor(self) -> PointCoord {
+        self.numer.div_euclid(self.denom)
+    }
+
+    /// The least integer not below `self`.
+    pub fn ceil(self) -> PointCoord {
+        -(-self.numer).div_euclid(self.denom)
+    }
+
+    /// The nearest integer, with halves rounded up.
+    pub fn round(self) -> PointCoord {
+        let floor = self.floor();
+        let rem = self.numer.rem_euclid(self.denom);
+        // If the denominator is 1, the remainder is 0, so the sum cannot overflow.
+        if rem >= self.denom - rem {
+            floor + 1
+        } else {
+            floor
+        }
+    }
+
+    /// The nearest `f64`, with ties to even, as for the primitive conversions.
+    pub fn to_f64(self) -> f64 {
+        let magnitude = divide(self.numer.unsigned_abs(), self.denom.unsigned_abs());
+        if self.numer < 0 {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (Wide::from(self.numer) * Wide::from(other.denom))
+            .cmp(&(Wide::from(other.numer) * Wide::from(self.denom)))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.denom == 1 {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+/// Correctly rounded `a / b`, for nonzero `b`.
+fn divide(a: impl Into<u128>, b: impl Into<u128>) -> f64 {
+    let (a, b) = (a.into(), b.into());
+    if a == 0 {
+        return 0.0;
+    }
+
+    // Find 54 significant bits of the quotient, one more than an f64 holds,
+    // so that the value is (mantissa + a fraction) * 2^exponent, with `sticky` set if the fraction is nonzero.
+    let (mut mantissa, mut rem) = (a / b, a % b);
+    let mut exponent = 0;
+    let mut sticky = false;
+    let bits = 128 - mantissa.leading_zeros() as i32;
+    if bits > 54 {
+        let shift = bits - 54;
+        sticky = mantissa & ((1 << shift) - 1) != 0;
+        mantissa >>= shift;
+        exponent += shift;
+    } else {
+        while mantissa < 1 << 53 {
+            // The remainder is less than b, which is at most 2^127, so doubling it fits.
+            rem *= 2;
+            mantissa *= 2;
+            if rem >= b {
+                rem -= b;
+                mantissa += 1;
+            }
+            exponent -= 1;
+        }
+    }
+    sticky |= rem != 0;
+
+    // Drop the extra bit, rounding half to even.
+    let half = mantissa & 1 == 1;
+    mantissa >>= 1;
+    exponent += 1;
+    if half && (sticky || mantissa & 1 == 1) {
+        mantissa += 1;
+    }
+    // Rounding up may carry into a new bit, which is still exact.
+    (mantissa as f64) * f64::from_bits(((exponent + 1023) as u64) << 52)
+}
+
+impl Point {
+    /// The x coordinate, exactly. None if the point is at infinity.
+    /// ```
+    /// # use polygon3::Point;
+    /// # use core::convert::TryInto;
+    /// let point: Point = [-6, 3, -4].try_into().unwrap();
+    ///
+    /// assert_eq!(point.x().unwrap().to_string(), "3/2");
+    /// assert_eq!(point.y().unwrap().to_string(), "-3/4");
+    /// assert_eq!(point.to_integer_floor(), Some([1, -1]));
+    /// assert_eq!(point.to_integer_ceil(), Some([2, 0]));
+    /// assert_eq!(point.to_integer_nearest(), Some([2, -1]));
+    /// ```
+    pub fn x(self) -> Option<Rational> {
+        let [x, _, z] = self.0;
+        Rational::new(x, z)
+    }
+
+    /// The y coordinate, exactly. None if the point is at infinity.
+    pub fn y(self) -> Option<Rational> {
+        let [_, y, z] = self.0;
+        Rational::new(y, z)
+    }
+
+    /// The nearest floating point coordinates. None if the point is at infinity.
+    ///
+    /// Each coordinate is rounded once, unlike dividing the results of `to_f64_array`.
+    pub fn to_f64_rounded(self) -> Option<[f64; 2]> {
+        Some([self.x()?.to_f64(), self.y()?.to_f64()])
+    }
+
+    /// Round each coordinate down. None if the point is at infinity.
+    pub fn to_integer_floor(self) -> Option<[PointCoord; 2]> {
+        Some([self.x()?.floor(), self.y()?.floor()])
+    }
+
+    /// Round each coordinate up. None if the point is at infinity.
+    pub fn to_integer_ceil(self) -> Option<[PointCoord; 2]> {
+        Some([self.x()?.ceil(), self.y()?.ceil()])
+    }
+
+    /// Round each coordinate to the nearest integer, with halves rounded up. None if the point is at infinity.
+    pub fn to_integer_nearest(self) -> Option<[PointCoord; 2]> {
+        Some([self.x()?.round(), self.y()?.round()])
+    }
+}
+
+#[test]
+fn test_rational() {
+    use std::convert::TryInto;
+
+    let ratio = |n: PointCoord, d: PointCoord| Rational::new(n, d).unwrap();
+    assert_eq!(ratio(6, -4), ratio(-3, 2));
+    assert_eq!(ratio(-3, 2).denom(), 2);
+    assert!(ratio(1, 3) < ratio(1, 2));
+    assert_eq!(Rational::new(1, 0), None);
+
+    for n in -7..=7 {
+        for d in 1..=4 {
+            let exact = n as f64 / d as f64;
+            let r = ratio(n, d);
+            assert_eq!(r.to_f64(), exact);
+            assert_eq!(r.floor() as f64, exact.floor());
+            assert_eq!(r.ceil() as f64, exact.ceil());
+            assert_eq!(r.round() as f64, (exact + 0.5).floor());
+        }
+    }
+
+    // Near the limits, dividing two rounded floats can be off by one unit in the last place.
+    let m = PointCoord::MAX;
+    let third = ratio(m, 3);
+    assert_eq!(third.floor(), m / 3);
+    assert_eq!(third.round(), m / 3);
+    assert_eq!(ratio(m, m - 1).to_f64(), 1.0);
+    assert_eq!(ratio(1, m).to_f64(), 1.0 / m as f64);
+    let big = (1 << 53) + 1;
+    assert_eq!(ratio(big, 1).to_f64(), (1u64 << 53) as f64);
+    assert_eq!(ratio(big + 2, 1).to_f64(), ((1u64 << 53) + 4) as f64);
+    assert_eq!(ratio(2 * big + 1, 2).to_f64(), ((1u64 << 53) + 2) as f64);
+
+    let point: Point = [7, -7, 2].try_into().unwrap();
+    assert_eq!(point.to_f64_rounded(), Some([3.5, -3.5]));
+    assert_eq!(point.to_integer_nearest(), Some([4, -3]));
+    let infinite: Point = [1, 0, 0].try_into().unwrap();
+    assert_eq!(infinite.x(), None);
+    assert_eq!(infinite.to_f64_rounded(), None);
+}
@@ -0,0 +1,221 @@
+use super::conversions::{MAX_F64_THAT_FITS_IN_LINE_COORD, MAX_F64_THAT_FITS_IN_POINT_COORD};
+use super::{Line, LineCoord, Point, PointCoord};
+
+/// How to round a line's position when converting it from floating point.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round each coefficient to the nearest integer.
+    Nearest,
+    /// Move the line so its positive side grows, and contains the original positive side.
+    Outward,
+    /// Move the line so its positive side shrinks, and is contained in the original positive side.
+    Inward,
+}
+
+/// The largest power of two that `max` can be scaled by, without exceeding `limit`.
+/// Scaling by a power of two is exact, so the only error is in the final rounding.
+pub(crate) fn power_of_two_scale(max: f64, limit: f64) -> f64 {
+    let mut s = 1.0;
+    while max * s > limit {
+        s /= 2.0;
+    }
+    while max * s * 2.0 <= limit {
+        s *= 2.0;
+    }
+    s
+}
+
+/// Inflate a bound computed in floating point, to cover the rounding error of the few operations it took.
+fn inflate(bound: f64) -> f64 {
+    bound * (1.0 + 8.0 * f64::EPSILON)
+}
+
+impl Line {
+    /// Convert from homogeneous coordinates, and bound how far the line moved.
+    ///
+    /// The result is only guaranteed within the square `|x|, |y| <= extent`.
+    /// Within it, the boundary moves by at most the returned distance,
+    /// and with `Rounding::Outward` or `Rounding::Inward`, its positive side contains or is contained in the original.
+    /// Converting every edge of a `ConvexPolygon` outward gives a polygon containing the original.
+    ///
+    /// None if the coefficients are not finite, if `a` and `b` are both zero,
+    /// or if the line is too far from the origin, relative to `extent`, to represent.
+    /// ```
+    /// # use polygon3::{Line, Rounding};
+    /// // 0.1 is not exactly representable, so x <= 0.1 must round one way or the other.
+    /// let (outward, error) = Line::try_from_f64_rounded([-1.0, 0.0, 0.1], Rounding::Outward, 10.0).unwrap();
+    /// let [a, _, c] = outward.to_f64_array();
+    /// assert!(-c / a >= 0.1);
+    /// assert!(-c / a - 0.1 <= error && error < 1e-8);
+    /// ```
+    pub fn try_from_f64_rounded(
+        [a, b, c]: [f64; 3],
+        rounding: Rounding,
+        extent: f64,
+    ) -> Option<(Self, f64)> {
+        if !(a.is_finite() && b.is_finite() && c.is_finite() && extent.is_finite()) {
+            return None;
+        }
+        let extent = extent.abs();
+        let limit = MAX_F64_THAT_FITS_IN_LINE_COORD;
+        let max = a.abs().max(b.abs());
+        if max == 0.0 {
+            return None;
+        }
+
+        // Leave room for the directed adjustment of c, which is at most the extent, plus rounding.
+        let mut s = power_of_two_scale(max.max(c.abs()), limit);
+        while c.abs() * s + extent + 2.0 > limit && s > 0.0 {
+            s /= 2.0;
+        }
+        let (a, b, c) = (a * s, b * s, c * s);
+        let (a2, b2) = (a.round(), b.round());
+        if a2 == 0.0 && b2 == 0.0 {
+            return None;
+        }
+
+        // Within the square, rounding a and b changes a*x + b*y by at most `tilt`.
+        let tilt = inflate((a2 - a).abs() + (b2 - b).abs()) * extent;
+        if tilt.ceil() > limit {
+            return None;
+        }
+        let (base, adjustment) = match rounding {
+            Rounding::Nearest => (c.round(), 0.0),
+            Rounding::Outward => (c.ceil(), tilt.ceil()),
+            Rounding::Inward => (c.floor(), -tilt.ceil()),
+        };
+        // Add in integers, since past 2^53, a floating point sum could round part of the adjustment away.
+        let c2 = (base as LineCoord)
+            .checked_add(adjustment as LineCoord)
+            .filter(|&c2| c2 != LineCoord::MIN)?;
+
+        // A point on either line is at most this far from the other, within the square.
+        let error =
+            inflate((tilt + (adjustment + (base - c)).abs()) / a.hypot(b).min(a2.hypot(b2)));
+        let line = Self([a2 as LineCoord, b2 as LineCoord, c2]);
+        Some((line, error))
+    }
+}
+
+impl Point {
+    /// Convert a finite point from homogeneous coordinates, and bound how far it moved.
+    ///
+    /// None if the coordinates are not finite, if the point is at infinity,
+    /// or if it is too far from the origin, relative to the precision of z, to represent.
+    /// ```
+    /// # use polygon3::Point;
+    /// let (point, error) = Point::try_from_f64_rounded([1.0, 0.1, 1.0]).unwrap();
+    /// let [x, y] = point.to_f64_rounded().unwrap();
+    /// assert_eq!(x, 1.0);
+    /// assert!((y - 0.1).abs() <= error && error < 1e-15);
+    /// ```
+    pub fn try_from_f64_rounded([a, b, c]: [f64; 3]) -> Option<(Self, f64)> {
+        if !(a.is_finite() && b.is_finite() && c.is_finite()) || c == 0.0 {
+            return None;
+        }
+        let s = power_of_two_scale(
+            a.abs().max(b.abs()).max(c.abs()),
+            MAX_F64_THAT_FITS_IN_POINT_COORD,
+        );
+        let (a, b, c) = (a * s, b * s, c * s);
+        let (a2, b2, c2) = (a.round(), b.round(), c.round());
+        if c2 == 0.0 {
+            return None;
+        }
+
+        // |a2 / c2 - a / c| = |(a2 - a) c - a (c2 - c)| / |c2 c|
+        let offset = |a: f64, a2: f64| {
+            ((a2 - a).abs() * c.abs() + a.abs() * (c2 - c).abs()) / (c2 * c).abs()
+        };
+        let error = inflate(offset(a, a2).hypot(offset(b, b2)));
+        let point = Self([a2 as PointCoord, b2 as PointCoord, c2 as PointCoord]);
+        Some((point, error))
+    }
+}
+
+#[test]
+fn test_rounding() {
+    use std::cmp::Ordering;
+    use std::convert::TryInto;
+
+    // Exactly representable inputs convert exactly.
+    let (line, error) =
+        Line::try_from_f64_rounded([0.5, -0.25, 3.0], Rounding::Outward, 100.0).unwrap();
+    assert_eq!(line, [2, -1, 12].try_into().unwrap());
+    assert_eq!(error, 0.0);
+
+    // A tilted line, rounded each way, brackets the original within the square.
+    let original = [1.0, std::f64::consts::PI, -std::f64::consts::E];
+    let extent = 1000.0;
+    let (outward, e1) = Line::try_from_f64_rounded(original, Rounding::Outward, extent).unwrap();
+    let (inward, e2) = Line::try_from_f64_rounded(original, Rounding::Inward, extent).unwrap();
+    let (nearest, e3) = Line::try_from_f64_rounded(original, Rounding::Nearest, extent).unwrap();
+    assert!(e1 < 1e-4 && e2 < 1e-4 && e3 < 1e-4);
+    for &x in &[-1000.0, -10.0, 0.0, 0.5, 999.0] {
+        for &y in &[-1000.0, 0.0, 0.865, 1000.0] {
+            let value = original[0] * x + original[1] * y + original[2];
+            let point: Point = Point::try_from_f64_array([x, y, 1.0]).unwrap();
+            if value > 0.0 {
+                assert_eq!(point.cmp_line(outward), Ordering::Greater);
+            }
+            if value < 0.0 {
+                assert_eq!(point.cmp_line(inward), Ordering::Less);
+            }
+            assert!(point.cmp_line(inward) <= point.cmp_line(nearest));
+            assert!(point.cmp_line(nearest) <= point.cmp_line(outward));
+        }
+    }
+
+    assert_eq!(
+        Line::try_from_f64_rounded([0.0, 0.0, 1.0], Rounding::Nearest, 1.0),
+        None
+    );
+    assert_eq!(
+        Line::try_from_f64_rounded([1.0, 0.0, f64::NAN], Rounding::Nearest, 1.0),
+        None
+    );
+    assert_eq!(
+        Line::try_from_f64_rounded([1.0, 0.0, 0.0], Rounding::Outward, 1e300),
+        None
+    );
+
+    let (point, error) = Point::try_from_f64_rounded([3.0, -4.5, 2.0]).unwrap();
+    assert_eq!(point, [6, -9, 4].try_into().unwrap());
+    assert_eq!(error, 0.0);
+    let (point, error) = Point::try_from_f64_rounded([1.0 / 3.0, 2.0 / 3.0, 1.0]).unwrap();
+    let [x, y] = point.to_f64_rounded().unwrap();
+    assert!((x - 1.0 / 3.0).hypot(y - 2.0 / 3.0) <= error);
+    assert_eq!(Point::try_from_f64_rounded([1.0, 1.0, 0.0]), None);
+}
+
+#[cfg(feature = "wide-coordinates")]
+#[test]
+fn test_rounding_wide() {
+    use std::cmp::Ordering;
+
+    // Past 2^53, the adjustment of c must not be lost to floating point rounding.
+    // Every input here is a multiple of 2^-54, so scaling by 2^54 makes them exact integers.
+    let exact = |x: f64| (x * 2f64.powi(54)) as i128;
+    let extent = 1e10;
+    for k in 0..100 {
+        let original = [1.0, 0.3, 1e10 + f64::from(k) * 1234.567];
+        let [a, b, c] = original.map(exact);
+        for &(rounding, side) in &[
+            (Rounding::Outward, Ordering::Greater),
+            (Rounding::Inward, Ordering::Less),
+        ] {
+            let (line, _) = Line::try_from_f64_rounded(original, rounding, extent).unwrap();
+            let [a2, b2, c2]: [LineCoord; 3] = line.into();
+            let [a2, b2, c2] = [a2, b2, c2].map(i128::from);
+            // The first coefficient is 1, so it was scaled by exactly `a2`.
+            // The rounded line must be at least the scaled original throughout the square, or at most for inward,
+            // and since both are linear, it is enough to check the corners.
+            for &(x, y) in &[(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                let (x, y) = (x * extent as i128, y * extent as i128);
+                let rounded = (a2 * x + b2 * y + c2) * exact(1.0);
+                let scaled = a2 * (a * x + b * y + c);
+                assert_ne!(scaled.cmp(&rounded), side);
+            }
+        }
+    }
+}
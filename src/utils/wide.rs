@@ -0,0 +1,241 @@
+// Fixed-width signed integers, with just enough arithmetic for exact predicates.
+// Multiplication, addition, and subtraction wrap, like the primitive integers in release mode,
+// so callers must make sure their results fit.
+
+use super::OverflowError;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// A signed integer of `N` 64-bit limbs, in two's complement, with the least significant limb first.
+/// `N` must be at least 2.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Int<const N: usize>([u64; N]);
+
+impl<const N: usize> Int<N> {
+    const ZERO: Self = Self([0; N]);
+
+    fn is_negative(self) -> bool {
+        self.0[N - 1] >> 63 == 1
+    }
+
+    fn abs(self) -> Self {
+        if self.is_negative() {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// Unsigned long division, one bit at a time.
+    fn div_rem_unsigned(n: [u64; N], d: [u64; N]) -> ([u64; N], [u64; N]) {
+        let mut q = [0; N];
+        let mut r = [0; N];
+        for bit in (0..64 * N).rev() {
+            // r = r * 2 + the next bit of n.
+            for i in (1..N).rev() {
+                r[i] = r[i] << 1 | r[i - 1] >> 63;
+            }
+            r[0] = r[0] << 1 | (n[bit / 64] >> (bit % 64) & 1);
+
+            if r.iter().rev().cmp(d.iter().rev()) != Ordering::Less {
+                r = (Self(r) - Self(d)).0;
+                q[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        (q, r)
+    }
+
+    /// Division rounding toward zero, like the primitive integers. The divisor must be nonzero.
+    fn div_rem(self, other: Self) -> (Self, Self) {
+        let (q, r) = Self::div_rem_unsigned(self.abs().0, other.abs().0);
+        let q = if self.is_negative() != other.is_negative() {
+            -Self(q)
+        } else {
+            Self(q)
+        };
+        let r = if self.is_negative() {
+            -Self(r)
+        } else {
+            Self(r)
+        };
+        (q, r)
+    }
+}
+
+impl<const N: usize> From<i128> for Int<N> {
+    fn from(x: i128) -> Self {
+        let extension = if x < 0 { !0 } else { 0 };
+        let mut limbs = [extension; N];
+        limbs[0] = x as u64;
+        limbs[1] = (x >> 64) as u64;
+        Self(limbs)
+    }
+}
+
+impl<const N: usize> From<i64> for Int<N> {
+    fn from(x: i64) -> Self {
+        Self::from(i128::from(x))
+    }
+}
+
+impl<const N: usize> From<i32> for Int<N> {
+    fn from(x: i32) -> Self {
+        Self::from(i128::from(x))
+    }
+}
+
+impl<const N: usize> TryFrom<Int<N>> for i128 {
+    type Error = OverflowError;
+    fn try_from(x: Int<N>) -> Result<Self, OverflowError> {
+        let narrow = (u128::from(x.0[1]) << 64 | u128::from(x.0[0])) as i128;
+        if Int::from(narrow) == x {
+            Ok(narrow)
+        } else {
+            Err(OverflowError)
+        }
+    }
+}
+
+impl<const N: usize> TryFrom<Int<N>> for i64 {
+    type Error = OverflowError;
+    fn try_from(x: Int<N>) -> Result<Self, OverflowError> {
+        i64::try_from(i128::try_from(x)?).map_err(|_| OverflowError)
+    }
+}
+
+impl<const N: usize> TryFrom<Int<N>> for i32 {
+    type Error = OverflowError;
+    fn try_from(x: Int<N>) -> Result<Self, OverflowError> {
+        i32::try_from(i128::try_from(x)?).map_err(|_| OverflowError)
+    }
+}
+
+impl<const N: usize> Ord for Int<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .is_negative()
+            .cmp(&self.is_negative())
+            .then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+}
+
+impl<const N: usize> PartialOrd for Int<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Neg for Int<N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self(self.0.map(|limb| !limb)) + Self::from(1)
+    }
+}
+
+impl<const N: usize> Add for Int<N> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        let mut out = [0; N];
+        let mut carry = 0;
+        for (out, (&a, &b)) in out.iter_mut().zip(self.0.iter().zip(&other.0)) {
+            let sum = u128::from(a) + u128::from(b) + carry;
+            *out = sum as u64;
+            carry = sum >> 64;
+        }
+        Self(out)
+    }
+}
+
+impl<const N: usize> Sub for Int<N> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl<const N: usize> Mul for Int<N> {
+    type Output = Self;
+    /// Two's complement makes the low bits of the product the same, signed or unsigned.
+    fn mul(self, other: Self) -> Self {
+        let mut out = [0; N];
+        for i in 0..N {
+            let mut carry = 0;
+            for j in 0..N - i {
+                let t =
+                    u128::from(out[i + j]) + u128::from(self.0[i]) * u128::from(other.0[j]) + carry;
+                out[i + j] = t as u64;
+                carry = t >> 64;
+            }
+        }
+        Self(out)
+    }
+}
+
+impl<const N: usize> Div for Int<N> {
+    type Output = Self;
+    fn div(self, other: Self) -> Self {
+        self.div_rem(other).0
+    }
+}
+
+impl<const N: usize> Rem for Int<N> {
+    type Output = Self;
+    fn rem(self, other: Self) -> Self {
+        self.div_rem(other).1
+    }
+}
+
+impl<const N: usize> std::iter::Sum for Int<N> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+#[test]
+fn test_int() {
+    let values: Vec<i128> = vec![
+        0,
+        1,
+        -1,
+        7,
+        -12,
+        i128::from(i64::MAX),
+        i128::from(i64::MIN),
+        i128::MAX,
+        i128::MIN + 1,
+    ];
+    for &a in &values {
+        for &b in &values {
+            let (wa, wb) = (Int::<4>::from(a), Int::<4>::from(b));
+            assert_eq!(wa.cmp(&wb), a.cmp(&b));
+            if let Some(sum) = a.checked_add(b) {
+                assert_eq!(wa + wb, Int::<4>::from(sum));
+            }
+            if let Some(product) = a.checked_mul(b) {
+                assert_eq!(wa * wb, Int::<4>::from(product));
+            }
+            if b != 0 {
+                assert_eq!(wa / wb, Int::<4>::from(a / b));
+                assert_eq!(wa % wb, Int::<4>::from(a % b));
+            }
+        }
+    }
+
+    // Products past 128 bits.
+    let big = Int::<4>::from(i128::MAX);
+    let square = big * big;
+    assert!(square > big);
+    assert_eq!(i128::try_from(square), Err(OverflowError));
+    assert_eq!(square / big, big);
+    assert_eq!((-square) / big, -big);
+    assert_eq!((square - Int::<4>::from(1)) % big, big - Int::<4>::from(1));
+    assert_eq!(i128::try_from(-big), Ok(-i128::MAX));
+
+    // Products past 256 bits.
+    let big = Int::<8>::from(i128::MAX);
+    let fourth = big * big * big * big;
+    assert!(fourth > big * big * big);
+    assert_eq!(fourth / big / big / big, big);
+    assert_eq!((-fourth) / (big * big), -(big * big));
+}